@@ -0,0 +1,111 @@
+use crate::conf::TetrisSettings;
+
+/// How far to shift the active tetromino horizontally in a game loop.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Shift {
+    None,
+    One,
+    ToWall,
+}
+
+/// Delayed auto shift (DAS) and auto repeat rate (ARR): when a direction
+/// button is held, the tetromino shifts once, then waits for the auto shift
+/// delay before repeatedly shifting at the auto repeat rate.
+pub struct AutoShift {
+    delay: u16,
+    rate: u16,
+}
+
+impl AutoShift {
+    pub fn new(settings: &TetrisSettings) -> Self {
+        Self {
+            delay: settings.auto_shift_delay,
+            rate: settings.auto_repeat_rate,
+        }
+    }
+
+    /// Compute the shift in this game loop, given the number of loops the
+    /// direction button has been held. If the tetromino has just spawned, the
+    /// auto shift charged while waiting for it (e.g. during entry delay) takes
+    /// effect immediately.
+    pub fn shift(&self, held_loops: u32, is_new_tetromino: bool) -> Shift {
+        if held_loops == 0 {
+            return Shift::None;
+        }
+        // The first loop of a button being held counts as 0 loops since pressed.
+        let loops_since_pressed = held_loops - 1;
+        if loops_since_pressed == 0 {
+            return Shift::One;
+        }
+        let delay = u32::from(self.delay);
+        if loops_since_pressed < delay {
+            return if is_new_tetromino {
+                Shift::One
+            } else {
+                Shift::None
+            };
+        }
+        if self.rate == 0 {
+            return Shift::ToWall;
+        }
+        let is_repeating = (loops_since_pressed - delay).is_multiple_of(u32::from(self.rate));
+        if is_repeating || is_new_tetromino {
+            Shift::One
+        } else {
+            Shift::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto_shift(delay: u16, rate: u16) -> AutoShift {
+        let settings = TetrisSettings {
+            auto_shift_delay: delay,
+            auto_repeat_rate: rate,
+            ..Default::default()
+        };
+        AutoShift::new(&settings)
+    }
+
+    fn shifts(auto_shift: &AutoShift, n_loops: u32) -> Vec<Shift> {
+        (1..=n_loops)
+            .map(|held_loops| auto_shift.shift(held_loops, false))
+            .collect()
+    }
+
+    #[test]
+    fn not_held() {
+        assert_eq!(auto_shift(3, 2).shift(0, false), Shift::None);
+        assert_eq!(auto_shift(3, 2).shift(0, true), Shift::None);
+    }
+
+    #[test]
+    fn shifts_once_then_repeats_after_delay() {
+        use Shift::*;
+        assert_eq!(
+            shifts(&auto_shift(3, 2), 8),
+            vec![One, None, None, One, None, One, None, One]
+        );
+    }
+
+    #[test]
+    fn zero_rate_shifts_to_wall() {
+        use Shift::*;
+        assert_eq!(
+            shifts(&auto_shift(2, 0), 4),
+            vec![One, None, ToWall, ToWall]
+        );
+    }
+
+    #[test]
+    fn new_tetromino_shifts_immediately() {
+        // DAS charged before the tetromino spawned.
+        assert_eq!(auto_shift(3, 2).shift(5, true), Shift::One);
+        assert_eq!(auto_shift(3, 0).shift(5, true), Shift::ToWall);
+        // Button held while the tetromino spawned, but DAS not charged yet.
+        assert_eq!(auto_shift(3, 2).shift(2, true), Shift::One);
+    }
+}
@@ -55,20 +55,97 @@ pub enum Button {
     Down,
     A,
     B,
+    X,
+    Y,
+    L,
     Select,
     Start,
+    Pause,
+}
+
+impl Button {
+    pub const ALL: [Button; 12] = [
+        Button::Left,
+        Button::Right,
+        Button::Up,
+        Button::Down,
+        Button::A,
+        Button::B,
+        Button::X,
+        Button::Y,
+        Button::L,
+        Button::Select,
+        Button::Start,
+        Button::Pause,
+    ];
 }
 
 pub trait GamePad {
-    fn direction(&self) -> (i16, i16);
-    fn is_pressed(&self, button: Button) -> bool;
+    /// The number of consecutive game loops the button has been held down,
+    /// starting from 1 in the loop it is pressed, or 0 if it is released.
+    fn held_loops(&self, button: Button) -> u32;
+
+    /// Whether the button has just been pressed in this game loop.
+    fn is_pressed(&self, button: Button) -> bool {
+        self.held_loops(button) == 1
+    }
+
     fn cheat_code(&self) -> Option<char>;
 }
 
+/// A game pad scripted by tests: the buttons held down with the number of
+/// loops they have been held, and the cheat code typed if any.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct TestGamePad {
+    pub held_loops: Vec<(Button, u32)>,
+    pub cheat_code: Option<char>,
+}
+
+#[cfg(test)]
+impl TestGamePad {
+    /// A game pad on which the button has just been pressed.
+    pub fn pressed(button: Button) -> Self {
+        Self {
+            held_loops: vec![(button, 1)],
+            cheat_code: None,
+        }
+    }
+
+    /// Move on to the next game loop, holding the given buttons down (for
+    /// one more loop if they were already held) and releasing the others.
+    pub fn hold(&mut self, buttons: &[Button]) {
+        self.held_loops = Button::ALL
+            .into_iter()
+            .filter(|button| buttons.contains(button))
+            .map(|button| (button, GamePad::held_loops(self, button) + 1))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+impl GamePad for TestGamePad {
+    fn held_loops(&self, button: Button) -> u32 {
+        self.held_loops
+            .iter()
+            .find(|(held_button, _)| *held_button == button)
+            .map_or(0, |&(_, held_loops)| held_loops)
+    }
+
+    fn cheat_code(&self) -> Option<char> {
+        self.cheat_code
+    }
+}
+
 /// Allow to draw onto the game UI.
 pub trait GameUI {
     fn draw_background(&mut self);
     fn draw_brick(&mut self, pos: Position, color: Color);
+    /// Draw a brick of the ghost piece, which shows where the active tetromino would land.
+    fn draw_ghost_brick(&mut self, pos: Position, color: Color);
+    /// Draw a brick of a row being cleared, `progress` going from 0 to 1
+    /// during the line clear delay.
+    fn draw_clearing_brick(&mut self, pos: Position, color: Color, progress: f32);
     fn draw_text(&mut self, pos: Position, msg: &str);
     fn draw_debugging_grids(&mut self);
 }
@@ -1,14 +1,69 @@
 use serde::{Deserialize, Serialize};
 
+use crate::lock::LockResetPolicy;
+use crate::progression::LevelProgressionName;
+use crate::randomizer::RandomizerName;
+use crate::rotation::RotationSystemName;
+use crate::scoring::ScoringName;
+use crate::topout::TopOutRule;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TetrisSettings {
     pub play_field_width: u8,
     pub play_field_height: u8,
+    /// Number of hidden rows above the visible play field, where the
+    /// tetrominoes spawn.
+    pub buffer_height: u8,
+    pub top_out_rule: TopOutRule,
+    pub scoring: ScoringName,
+    /// Scores for 1, 2, 3 and 4 rows cleared, for `ScoringName::Simple`.
     pub scores_for_rows_destroyed: [u32; 4],
+    pub level_progression: LevelProgressionName,
+    /// Score to reach each level, for `LevelProgressionName::Score`.
     pub score_per_level: u32,
-    pub fall_pace_slowest: u8,
-    pub fall_pace_fastest: u8,
+    /// Rows to clear for each level, for `LevelProgressionName::Lines`.
+    pub lines_per_level: u32,
+    /// The level games start from by default, which can be changed in the menu.
+    pub start_level: u8,
+    /// Number of rows to clear to complete a game in `GameMode::Sprint`.
+    pub sprint_lines: u32,
+    /// Duration of a game in `GameMode::Ultra`, in seconds.
+    pub ultra_seconds: u32,
+    /// Number of game loops per second. All the timings counted in game loops
+    /// depend on it, except gravity.
+    pub loops_per_second: u16,
+    /// Gravity in G (cells per 1/60 second) for each level from 0, the last
+    /// entry applying to any higher level. 20G makes the tetromino fall to the
+    /// bottom instantly.
+    pub gravity: Vec<f32>,
     pub enable_cheating: bool,
+    pub randomizer: RandomizerName,
+    pub rotation_system: RotationSystemName,
+    pub enable_hold: bool,
+    /// Number of tetrominoes shown in the next queue, from 1 to 6.
+    pub next_queue_length: u8,
+    pub show_ghost: bool,
+    /// Number of game loops a tetromino can stay on the ground before being locked.
+    pub lock_delay: u16,
+    pub lock_reset_policy: LockResetPolicy,
+    /// Max number of lock delay resets for `LockResetPolicy::MoveReset`.
+    pub lock_reset_limit: u8,
+    /// Number of game loops a direction button must be held before the
+    /// tetromino starts shifting repeatedly (DAS).
+    pub auto_shift_delay: u16,
+    /// Number of game loops between 2 repeated shifts (ARR). If 0, the
+    /// tetromino shifts to the wall instantly.
+    pub auto_repeat_rate: u16,
+    /// How many times faster than gravity the tetromino falls when soft-dropped. If 0, the
+    /// tetromino falls to the bottom instantly (sonic drop), without locking.
+    pub soft_drop_factor: u16,
+    /// Number of game loops to wait before spawning the next tetromino (ARE).
+    pub entry_delay: u16,
+    /// Number of game loops to wait before destroying the completed rows.
+    pub line_clear_delay: u16,
+    /// Seed of the random number generator. The same seed always yields the
+    /// same game. A random seed is used if not set.
+    pub seed: Option<u64>,
 }
 
 impl Default for TetrisSettings {
@@ -16,11 +71,37 @@ impl Default for TetrisSettings {
         Self {
             play_field_width: 10,
             play_field_height: 20,
+            buffer_height: 20,
+            top_out_rule: TopOutRule::LockOut,
+            scoring: ScoringName::Simple,
             scores_for_rows_destroyed: [10, 30, 50, 100],
+            level_progression: LevelProgressionName::Score,
             score_per_level: 200,
-            fall_pace_slowest: 20,
-            fall_pace_fastest: 3,
+            lines_per_level: 10,
+            start_level: 0,
+            sprint_lines: 40,
+            ultra_seconds: 120,
+            loops_per_second: 40,
+            // Guideline: (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row.
+            gravity: vec![
+                0.01667, 0.021, 0.027, 0.0353, 0.0469, 0.0636, 0.0879, 0.1237, 0.1775, 0.2598,
+                0.3878, 0.5906, 0.9181, 1.457, 2.3612, 3.9091, 6.6135, 11.4379, 20.0,
+            ],
             enable_cheating: true,
+            randomizer: RandomizerName::SevenBag,
+            rotation_system: RotationSystemName::Srs,
+            enable_hold: true,
+            next_queue_length: 5,
+            show_ghost: true,
+            lock_delay: 20,
+            lock_reset_policy: LockResetPolicy::MoveReset,
+            lock_reset_limit: 15,
+            auto_shift_delay: 6,
+            auto_repeat_rate: 2,
+            soft_drop_factor: 20,
+            entry_delay: 4,
+            line_clear_delay: 12,
+            seed: None,
         }
     }
 }
@@ -0,0 +1,116 @@
+use crate::conf::TetrisSettings;
+
+/// Gravity at or above which the tetromino falls to the bottom instantly.
+pub const MAX_GRAVITY: f32 = 20.0;
+
+/// Gravity is measured in G, i.e. cells per frame of 1/60 second, no matter
+/// how many game loops there are per second.
+const FRAMES_PER_SECOND: f32 = 60.0;
+
+/// Make the active tetromino fall at the gravity of the current level,
+/// accumulating the fractional cells across game loops.
+pub struct Gravity {
+    table: Vec<f32>,
+    loops_per_second: f32,
+    progress: f32,
+}
+
+impl Gravity {
+    pub fn new(settings: &TetrisSettings) -> Self {
+        Self {
+            table: settings.gravity.clone(),
+            loops_per_second: f32::from(settings.loops_per_second.max(1)),
+            progress: 0.0,
+        }
+    }
+
+    /// The gravity in G at the given level, the last entry of the table
+    /// applying to any higher level.
+    pub fn at_level(&self, level: u8) -> f32 {
+        let gravity = self
+            .table
+            .get(level as usize)
+            .or(self.table.last())
+            .copied()
+            .unwrap_or(1.0 / FRAMES_PER_SECOND);
+        gravity.clamp(0.0, MAX_GRAVITY)
+    }
+
+    /// Forget the fractional cells accumulated so far, e.g. for a new
+    /// tetromino or when the tetromino is on the ground.
+    pub fn reset(&mut self) {
+        self.progress = 0.0;
+    }
+
+    /// Count one game loop falling at `factor` times the gravity of the given
+    /// level, returning the number of cells to fall by. At 20G, this is
+    /// `u32::MAX`, i.e. to the bottom.
+    pub fn tick(&mut self, level: u8, factor: f32) -> u32 {
+        let gravity = self.at_level(level) * factor;
+        if gravity >= MAX_GRAVITY {
+            self.progress = 0.0;
+            return u32::MAX;
+        }
+        self.progress += gravity * FRAMES_PER_SECOND / self.loops_per_second;
+        let n_cells = self.progress.floor();
+        self.progress -= n_cells;
+        n_cells as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gravity(table: Vec<f32>, loops_per_second: u16) -> Gravity {
+        let settings = TetrisSettings {
+            gravity: table,
+            loops_per_second,
+            ..Default::default()
+        };
+        Gravity::new(&settings)
+    }
+
+    fn n_cells_in_loops(gravity: &mut Gravity, level: u8, n_loops: u32) -> u32 {
+        (0..n_loops).map(|_| gravity.tick(level, 1.0)).sum()
+    }
+
+    #[test]
+    fn table_lookup() {
+        let gravity = gravity(vec![0.5, 1.0, 50.0], 60);
+        assert_eq!(gravity.at_level(0), 0.5);
+        assert_eq!(gravity.at_level(1), 1.0);
+        assert_eq!(gravity.at_level(2), MAX_GRAVITY);
+        assert_eq!(gravity.at_level(9), MAX_GRAVITY);
+    }
+
+    #[test]
+    fn fractional_gravity() {
+        // 1/4 G: one cell every 4 frames, i.e. 15 cells per second.
+        let mut gravity = gravity(vec![0.25], 60);
+        let n_cells: Vec<u32> = (0..8).map(|_| gravity.tick(0, 1.0)).collect();
+        assert_eq!(n_cells, vec![0, 0, 0, 1, 0, 0, 0, 1]);
+
+        gravity.tick(0, 1.0);
+        gravity.reset();
+        assert_eq!(n_cells_in_loops(&mut gravity, 0, 3), 0);
+    }
+
+    #[test]
+    fn independent_of_loop_rate() {
+        for loops_per_second in [30, 40, 60, 120] {
+            let mut gravity = gravity(vec![0.5], loops_per_second);
+            // 30 cells per second.
+            let n_loops = u32::from(loops_per_second);
+            assert_eq!(n_cells_in_loops(&mut gravity, 0, n_loops), 30);
+        }
+    }
+
+    #[test]
+    fn factor_and_max_gravity() {
+        let mut gravity = gravity(vec![0.5, 20.0], 60);
+        assert_eq!(gravity.tick(0, 4.0), 2);
+        assert_eq!(gravity.tick(0, 40.0), u32::MAX);
+        assert_eq!(gravity.tick(1, 1.0), u32::MAX);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mode::GameMode;
+
+/// A finished game worth remembering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub level: u8,
+    pub n_lines: u32,
+    /// Duration of the game in hundredths of a second, whatever the rate of
+    /// the game loops.
+    pub centis: u32,
+}
+
+/// The best games of each mode, best first. It is up to the front end to save
+/// and load them across sessions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScores {
+    tables: BTreeMap<GameMode, Vec<HighScore>>,
+}
+
+impl HighScores {
+    /// Number of games kept in the table of each mode.
+    pub const MAX_ENTRIES: usize = 10;
+
+    pub fn entries(&self, mode: GameMode) -> &[HighScore] {
+        self.tables.get(&mode).map_or(&[], |entries| entries)
+    }
+
+    /// Insert a game into the table of its mode, returning its rank (from 0)
+    /// if it is good enough to be kept. Older games rank first on ties.
+    pub fn insert(&mut self, mode: GameMode, entry: HighScore) -> Option<usize> {
+        let entries = self.tables.entry(mode).or_default();
+        let rank = entries
+            .iter()
+            .position(|other| ranks_before(mode, &entry, other))
+            .unwrap_or(entries.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+        entries.insert(rank, entry);
+        entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// Whether a game strictly beats another one: Sprint games are ranked by
+/// time, the other modes by score.
+fn ranks_before(mode: GameMode, entry: &HighScore, other: &HighScore) -> bool {
+    match mode {
+        GameMode::Sprint => entry.centis < other.centis,
+        GameMode::Marathon | GameMode::Ultra => entry.score > other.score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32) -> HighScore {
+        HighScore {
+            score,
+            level: 0,
+            n_lines: 0,
+            centis: 0,
+        }
+    }
+
+    fn timed_entry(centis: u32) -> HighScore {
+        HighScore { centis, ..entry(0) }
+    }
+
+    #[test]
+    fn insert() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(100)), Some(0));
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(300)), Some(0));
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(100)), Some(2));
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(200)), Some(1));
+        let scores: Vec<u32> = high_scores
+            .entries(GameMode::Marathon)
+            .iter()
+            .map(|e| e.score)
+            .collect();
+        assert_eq!(scores, vec![300, 200, 100, 100]);
+    }
+
+    #[test]
+    fn keep_the_best_only() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=HighScores::MAX_ENTRIES as u32 {
+            high_scores.insert(GameMode::Marathon, entry(score * 10));
+        }
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(5)), None);
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(10)), None);
+        assert_eq!(high_scores.insert(GameMode::Marathon, entry(15)), Some(9));
+        assert_eq!(
+            high_scores.entries(GameMode::Marathon).len(),
+            HighScores::MAX_ENTRIES
+        );
+        assert_eq!(high_scores.entries(GameMode::Marathon)[9].score, 15);
+    }
+
+    #[test]
+    fn sprint_is_ranked_by_time() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(
+            high_scores.insert(GameMode::Sprint, timed_entry(3000)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(GameMode::Sprint, timed_entry(2000)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(GameMode::Sprint, timed_entry(4000)),
+            Some(2)
+        );
+        // Each mode has its own table.
+        assert_eq!(high_scores.insert(GameMode::Ultra, entry(100)), Some(0));
+        assert!(high_scores.entries(GameMode::Marathon).is_empty());
+        let times: Vec<u32> = high_scores
+            .entries(GameMode::Sprint)
+            .iter()
+            .map(|e| e.centis)
+            .collect();
+        assert_eq!(times, vec![2000, 3000, 4000]);
+    }
+}
@@ -1,10 +1,32 @@
+mod autoshift;
 mod common;
 mod conf;
+mod gravity;
+mod highscores;
+mod lock;
+mod mode;
 mod playfield;
+mod progression;
+mod random;
+mod randomizer;
+mod replay;
+mod rotation;
+mod scoring;
 mod states;
+mod statistics;
 mod tetris;
 mod tetromino;
+mod topout;
+mod tspin;
 
 pub use common::{Button, Color, GamePad, GameUI, Position};
 pub use conf::TetrisSettings;
+pub use highscores::{HighScore, HighScores};
+pub use lock::LockResetPolicy;
+pub use mode::GameMode;
+pub use progression::LevelProgressionName;
+pub use randomizer::RandomizerName;
+pub use rotation::RotationSystemName;
+pub use scoring::ScoringName;
 pub use tetris::Tetris;
+pub use topout::TopOutRule;
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::conf::TetrisSettings;
+
+/// The rules to reset the lock delay timer while the tetromino is on the ground.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockResetPolicy {
+    /// Every move or rotation resets the timer, without any limit.
+    Infinite,
+    /// Every move or rotation resets the timer, up to a limited number of
+    /// times, after which the tetromino locks as soon as it is on the ground.
+    /// The counter is reset when the tetromino falls below its lowest row.
+    MoveReset,
+    /// Only falling by one row resets the timer (classic rules).
+    StepReset,
+}
+
+/// The lock delay timer, counting the game loops the active tetromino has
+/// spent on the ground before being locked into the play field.
+pub struct LockDelay {
+    policy: LockResetPolicy,
+    delay: u16,
+    reset_limit: u8,
+    elapsed: u16,
+    n_resets: u8,
+    lowest_row: i16,
+}
+
+impl LockDelay {
+    pub fn new(settings: &TetrisSettings) -> Self {
+        Self {
+            policy: settings.lock_reset_policy,
+            delay: settings.lock_delay,
+            reset_limit: settings.lock_reset_limit,
+            elapsed: 0,
+            n_resets: 0,
+            lowest_row: i16::MIN,
+        }
+    }
+
+    /// Restart the timer for a new tetromino.
+    pub fn restart(&mut self) {
+        self.elapsed = 0;
+        self.n_resets = 0;
+        self.lowest_row = i16::MIN;
+    }
+
+    /// Count one game loop spent on the ground, returning whether the
+    /// tetromino should be locked now.
+    pub fn tick(&mut self) -> bool {
+        if self.policy == LockResetPolicy::MoveReset && self.n_resets >= self.reset_limit {
+            return true;
+        }
+        self.elapsed += 1;
+        self.elapsed >= self.delay
+    }
+
+    /// Notify that the tetromino has been moved or rotated.
+    pub fn on_moved(&mut self) {
+        match self.policy {
+            LockResetPolicy::Infinite => self.elapsed = 0,
+            LockResetPolicy::MoveReset => {
+                if self.n_resets < self.reset_limit {
+                    self.elapsed = 0;
+                    self.n_resets += 1;
+                }
+            }
+            LockResetPolicy::StepReset => {}
+        }
+    }
+
+    /// Notify that the tetromino has fallen, its bottom now being at the given row.
+    pub fn on_fallen(&mut self, bottom_row: i16) {
+        if bottom_row > self.lowest_row {
+            self.lowest_row = bottom_row;
+            self.elapsed = 0;
+            self.n_resets = 0;
+        } else if self.policy != LockResetPolicy::MoveReset {
+            self.elapsed = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_delay(policy: LockResetPolicy) -> LockDelay {
+        let settings = TetrisSettings {
+            lock_delay: 3,
+            lock_reset_policy: policy,
+            lock_reset_limit: 2,
+            ..Default::default()
+        };
+        LockDelay::new(&settings)
+    }
+
+    #[test]
+    fn locks_after_delay() {
+        let mut lock_delay = lock_delay(LockResetPolicy::Infinite);
+        assert!(!lock_delay.tick());
+        assert!(!lock_delay.tick());
+        assert!(lock_delay.tick());
+    }
+
+    #[test]
+    fn infinite_resets_on_every_move() {
+        let mut lock_delay = lock_delay(LockResetPolicy::Infinite);
+        for _ in 0..10 {
+            assert!(!lock_delay.tick());
+            assert!(!lock_delay.tick());
+            lock_delay.on_moved();
+        }
+    }
+
+    #[test]
+    fn move_reset_is_limited() {
+        let mut lock_delay = lock_delay(LockResetPolicy::MoveReset);
+        for _ in 0..2 {
+            assert!(!lock_delay.tick());
+            assert!(!lock_delay.tick());
+            lock_delay.on_moved();
+        }
+        // No more reset: lock as soon as on the ground.
+        assert!(lock_delay.tick());
+    }
+
+    #[test]
+    fn move_reset_counter_is_reset_on_a_new_lowest_row() {
+        let mut lock_delay = lock_delay(LockResetPolicy::MoveReset);
+        lock_delay.on_fallen(10);
+        lock_delay.on_moved();
+        lock_delay.on_moved();
+        lock_delay.on_fallen(11);
+        lock_delay.on_moved();
+        assert!(!lock_delay.tick());
+
+        // Falling again to a row which is not lower does not reset the counter.
+        lock_delay.on_moved();
+        lock_delay.on_fallen(11);
+        assert!(lock_delay.tick());
+    }
+
+    #[test]
+    fn step_reset_ignores_moves() {
+        let mut lock_delay = lock_delay(LockResetPolicy::StepReset);
+        assert!(!lock_delay.tick());
+        assert!(!lock_delay.tick());
+        lock_delay.on_moved();
+        assert!(lock_delay.tick());
+
+        lock_delay.restart();
+        assert!(!lock_delay.tick());
+        assert!(!lock_delay.tick());
+        lock_delay.on_fallen(5);
+        assert!(!lock_delay.tick());
+        assert!(!lock_delay.tick());
+        assert!(lock_delay.tick());
+    }
+}
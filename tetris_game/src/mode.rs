@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::conf::TetrisSettings;
+
+/// The highest level a game can start from.
+pub const MAX_START_LEVEL: u8 = 19;
+
+/// The game modes, deciding when a game ends and how it is ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// Endless game until topping out, ranked by score.
+    Marathon,
+    /// Clear `sprint_lines` rows as fast as possible, ranked by time.
+    Sprint,
+    /// Score as much as possible in `ultra_seconds`, ranked by score.
+    Ultra,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Marathon, GameMode::Sprint, GameMode::Ultra];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Sprint => "Sprint",
+            GameMode::Ultra => "Ultra",
+        }
+    }
+
+    /// The mode before or after this one in `ALL`, by `step` modes.
+    pub fn cycle(&self, step: isize) -> GameMode {
+        let modes = GameMode::ALL;
+        let index = modes
+            .iter()
+            .position(|mode| mode == self)
+            .unwrap_or_default();
+        let index = (index as isize + step).rem_euclid(modes.len() as isize);
+        modes[index as usize]
+    }
+}
+
+/// The choices of the player for a game, on top of the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOptions {
+    pub mode: GameMode,
+    /// The level to start from, the lower levels being skipped.
+    pub start_level: u8,
+}
+
+impl GameOptions {
+    /// The default options, as set in the settings.
+    pub fn new(settings: &TetrisSettings) -> Self {
+        Self {
+            mode: GameMode::Marathon,
+            start_level: settings.start_level.min(MAX_START_LEVEL),
+        }
+    }
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::Marathon,
+            start_level: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_level_is_clamped() {
+        for (start_level, expected) in
+            [(0, 0), (7, 7), (MAX_START_LEVEL, MAX_START_LEVEL), (25, 19)]
+        {
+            let settings = TetrisSettings {
+                start_level,
+                ..Default::default()
+            };
+            assert_eq!(GameOptions::new(&settings).start_level, expected);
+        }
+    }
+}
@@ -6,14 +6,18 @@ use super::tetromino::GameWorld;
 pub struct PlayField {
     width: u8,
     height: u8,
+    buffer_height: u8,
     space: HashMap<Position, Color>,
 }
 
 impl PlayField {
-    pub fn new(width: u8, height: u8) -> Self {
+    /// Create an empty play field of `width` x `height` visible rows, plus
+    /// `buffer_height` hidden rows above them (at negative `y`).
+    pub fn new(width: u8, height: u8, buffer_height: u8) -> Self {
         Self {
             width,
             height,
+            buffer_height,
             space: HashMap::new(),
         }
     }
@@ -26,6 +30,10 @@ impl PlayField {
         self.height as i16
     }
 
+    pub fn buffer_height(&self) -> i16 {
+        self.buffer_height as i16
+    }
+
     pub fn space(&self) -> &HashMap<Position, Color> {
         &self.space
     }
@@ -43,8 +51,8 @@ impl PlayField {
         }
     }
 
-    pub fn destroy_completed_rows(&mut self) -> i16 {
-        let rows_completed: Vec<i16> = (0..self.height())
+    pub fn completed_rows(&self) -> Vec<i16> {
+        (-self.buffer_height()..self.height())
             .filter(|&row| {
                 let n_filled = self
                     .space
@@ -56,7 +64,18 @@ impl PlayField {
                     .count() as i16;
                 n_filled == self.width()
             })
-            .collect();
+            .collect()
+    }
+
+    /// Whether destroying the completed rows (if any) would leave the play
+    /// field empty.
+    pub fn is_perfect_clear(&self) -> bool {
+        let rows = self.completed_rows();
+        !rows.is_empty() && self.space.len() == rows.len() * self.width as usize
+    }
+
+    pub fn destroy_completed_rows(&mut self) -> i16 {
+        let rows_completed = self.completed_rows();
         self.destroy_rows(&rows_completed);
         rows_completed.len() as i16
     }
@@ -100,7 +119,7 @@ impl GameWorld for PlayField {
     fn is_free(&self, positions: &[crate::common::Position]) -> bool {
         positions.iter().all(|position| {
             let (x, y) = position.xy();
-            (0..self.width()).contains(&x) && (0..self.height()).contains(&y) // not out of bound
+            (0..self.width()).contains(&x) && (-self.buffer_height()..self.height()).contains(&y) // not out of bound
             && !self.space.contains_key(position) // not occupied
         })
     }
@@ -112,7 +131,7 @@ mod tests {
 
     #[test]
     fn destroy_completed_rows() {
-        let mut field = PlayField::new(10, 20);
+        let mut field = PlayField::new(10, 20, 0);
         let n = field.height() - 6;
 
         let get_row = |xs: Vec<i16>, y: i16| -> Vec<Position> {
@@ -166,9 +185,40 @@ mod tests {
         assert_eq!(field.space(), &expected_space);
     }
 
+    #[test]
+    fn buffer_rows() {
+        let mut field = PlayField::new(4, 20, 2);
+        assert!(field.is_free(&[Position::new(0, -2), Position::new(3, 19)]));
+        assert!(!field.is_free(&[Position::new(0, -3)]));
+
+        // Rows in the buffer can be cleared too.
+        let row: Vec<Position> = (0..4).map(|x| Position::new(x, -1)).collect();
+        field.fill_space(&row, Color::Teal);
+        field.fill_space(&[Position::new(0, -2)], Color::Teal);
+        assert_eq!(field.completed_rows(), vec![-1]);
+        field.destroy_completed_rows();
+        let expected_space: HashMap<Position, Color> =
+            [(Position::new(0, -1), Color::Teal)].into_iter().collect();
+        assert_eq!(field.space(), &expected_space);
+    }
+
+    #[test]
+    fn is_perfect_clear() {
+        let mut field = PlayField::new(4, 20, 0);
+        assert!(!field.is_perfect_clear());
+
+        let row = |y: i16| -> Vec<Position> { (0..4).map(|x| Position::new(x, y)).collect() };
+        field.fill_space(&row(19), Color::Teal);
+        field.fill_space(&row(18), Color::Teal);
+        assert!(field.is_perfect_clear());
+
+        field.fill_space(&[Position::new(0, 17)], Color::Teal);
+        assert!(!field.is_perfect_clear());
+    }
+
     #[test]
     fn fade_to_gray() {
-        let mut field = PlayField::new(10, 20);
+        let mut field = PlayField::new(10, 20, 0);
         let positions = vec![
             Position::new(0, 0),
             Position::new(1, 1),
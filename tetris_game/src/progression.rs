@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::conf::TetrisSettings;
+use crate::scoring::Clear;
+use crate::tspin::TSpin;
+
+/// The rules to level up during a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelProgressionName {
+    /// A level every `score_per_level` points.
+    Score,
+    /// A level every `lines_per_level` rows cleared. When starting above
+    /// level 0, the first level-up comes later, like on the NES.
+    Lines,
+    /// Guideline variable goal: level N (from 1) needs 5 * N lines, awarded
+    /// by the kind of clear, e.g. 8 for a tetris.
+    VariableGoal,
+}
+
+/// A level progression rule set, keeping track of what has been cleared.
+pub trait LevelProgression {
+    /// Count a locked tetromino and what it cleared, with the score so far.
+    fn on_lock(&mut self, clear: Clear, score: u32);
+
+    /// The current level, from 0.
+    fn level(&self) -> u8;
+}
+
+pub fn create_level_progression(
+    settings: &TetrisSettings,
+    start_level: u8,
+) -> Box<dyn LevelProgression> {
+    match settings.level_progression {
+        LevelProgressionName::Score => Box::new(ScoreProgression {
+            score_per_level: settings.score_per_level.max(1),
+            start_level,
+            score: 0,
+        }),
+        LevelProgressionName::Lines => Box::new(LinesProgression {
+            lines_per_level: settings.lines_per_level.max(1),
+            start_level,
+            n_lines: 0,
+        }),
+        LevelProgressionName::VariableGoal => Box::new(VariableGoalProgression {
+            level: start_level,
+            n_goal_lines: 0,
+            is_back_to_back: false,
+        }),
+    }
+}
+
+fn to_level(level: u32) -> u8 {
+    level.min(u32::from(u8::MAX)) as u8
+}
+
+pub struct ScoreProgression {
+    score_per_level: u32,
+    start_level: u8,
+    score: u32,
+}
+
+impl LevelProgression for ScoreProgression {
+    fn on_lock(&mut self, _clear: Clear, score: u32) {
+        self.score = score;
+    }
+
+    /// The level reached by score, but not lower than the starting level.
+    fn level(&self) -> u8 {
+        to_level(self.score / self.score_per_level).max(self.start_level)
+    }
+}
+
+pub struct LinesProgression {
+    lines_per_level: u32,
+    start_level: u8,
+    n_lines: u32,
+}
+
+impl LinesProgression {
+    /// Number of lines for the first level-up, like on the NES: the lines of
+    /// the levels up to the starting one, capped to `max(10, start_level - 5)`
+    /// levels worth of lines (e.g. 60 lines from level 5, 130 from level 18).
+    fn first_level_up_lines(&self) -> u32 {
+        let n = self.lines_per_level;
+        let start_level = u32::from(self.start_level);
+        let at_least = (10 * n).max((start_level * n).saturating_sub(5 * n));
+        ((start_level + 1) * n).min(at_least)
+    }
+}
+
+impl LevelProgression for LinesProgression {
+    fn on_lock(&mut self, clear: Clear, _score: u32) {
+        self.n_lines += clear.n_rows;
+    }
+
+    fn level(&self) -> u8 {
+        let first_level_up_lines = self.first_level_up_lines();
+        if self.n_lines < first_level_up_lines {
+            return self.start_level;
+        }
+        let n_level_ups = 1 + (self.n_lines - first_level_up_lines) / self.lines_per_level;
+        to_level(u32::from(self.start_level) + n_level_ups)
+    }
+}
+
+pub struct VariableGoalProgression {
+    level: u8,
+    /// Lines awarded since the last level-up.
+    n_goal_lines: u32,
+    is_back_to_back: bool,
+}
+
+impl VariableGoalProgression {
+    // Lines awarded indexed by the number of rows cleared, from 0.
+    const LINES: [u32; 5] = [0, 1, 3, 5, 8];
+    const T_SPIN_MINI_LINES: [u32; 3] = [1, 2, 4];
+    const T_SPIN_LINES: [u32; 4] = [4, 8, 12, 16];
+
+    /// Number of lines to award to level up from the current level.
+    fn goal(&self) -> u32 {
+        5 * (u32::from(self.level) + 1)
+    }
+}
+
+impl LevelProgression for VariableGoalProgression {
+    fn on_lock(&mut self, clear: Clear, _score: u32) {
+        let lines: &[u32] = match clear.t_spin {
+            TSpin::None => &Self::LINES,
+            TSpin::Mini => &Self::T_SPIN_MINI_LINES,
+            TSpin::Full => &Self::T_SPIN_LINES,
+        };
+        let index = (clear.n_rows as usize).min(lines.len() - 1);
+        let mut n_lines = lines[index];
+        if clear.n_rows > 0 {
+            if clear.is_difficult() && self.is_back_to_back {
+                n_lines = n_lines * 3 / 2;
+            }
+            self.is_back_to_back = clear.is_difficult();
+        }
+
+        self.n_goal_lines += n_lines;
+        while self.n_goal_lines >= self.goal() && self.level < u8::MAX {
+            self.n_goal_lines -= self.goal();
+            self.level += 1;
+        }
+    }
+
+    fn level(&self) -> u8 {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(name: LevelProgressionName, start_level: u8) -> Box<dyn LevelProgression> {
+        let settings = TetrisSettings {
+            level_progression: name,
+            score_per_level: 100,
+            lines_per_level: 10,
+            ..Default::default()
+        };
+        create_level_progression(&settings, start_level)
+    }
+
+    fn clear(n_rows: u32) -> Clear {
+        Clear {
+            n_rows,
+            t_spin: TSpin::None,
+            is_perfect_clear: false,
+        }
+    }
+
+    /// Clear rows one at a time, returning the level after each clear.
+    fn levels_by_lines(progression: &mut dyn LevelProgression, n_lines: u32) -> Vec<u8> {
+        (0..n_lines)
+            .map(|_| {
+                progression.on_lock(clear(1), 0);
+                progression.level()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn score() {
+        let mut progression = create(LevelProgressionName::Score, 0);
+        assert_eq!(progression.level(), 0);
+        progression.on_lock(clear(1), 250);
+        assert_eq!(progression.level(), 2);
+
+        // No level-up until the score of the level above the starting one.
+        let mut progression = create(LevelProgressionName::Score, 3);
+        progression.on_lock(clear(1), 250);
+        assert_eq!(progression.level(), 3);
+        progression.on_lock(clear(1), 400);
+        assert_eq!(progression.level(), 4);
+    }
+
+    #[test]
+    fn lines() {
+        let mut progression = create(LevelProgressionName::Lines, 0);
+        let levels = levels_by_lines(progression.as_mut(), 25);
+        assert_eq!(levels[8], 0);
+        assert_eq!(levels[9], 1);
+        assert_eq!(levels[19], 2);
+        assert_eq!(levels[24], 2);
+    }
+
+    #[test]
+    fn lines_from_start_level() {
+        // The first level-up happens after the lines of the levels up to the
+        // starting one, between 100 and 10 levels worth of lines below it.
+        let expected = [(0, 10), (5, 60), (9, 100), (12, 100), (18, 130)];
+        for (start_level, first_level_up_lines) in expected {
+            let mut progression = create(LevelProgressionName::Lines, start_level);
+            let levels = levels_by_lines(progression.as_mut(), first_level_up_lines + 10);
+            let n = first_level_up_lines as usize;
+            assert_eq!(levels[n - 2], start_level);
+            assert_eq!(levels[n - 1], start_level + 1);
+            assert_eq!(levels[n + 9], start_level + 2);
+        }
+    }
+
+    #[test]
+    fn variable_goal() {
+        let mut progression = create(LevelProgressionName::VariableGoal, 0);
+        // Level 1 needs 5 lines, level 2 needs 10 lines.
+        progression.on_lock(clear(4), 0);
+        assert_eq!(progression.level(), 1);
+        // Back-to-back tetris: 8 * 1.5 lines, with 3 lines left from before.
+        progression.on_lock(clear(4), 0);
+        assert_eq!(progression.level(), 2);
+        // A T-spin without clear awards lines too.
+        let t_spin = Clear {
+            n_rows: 0,
+            t_spin: TSpin::Full,
+            is_perfect_clear: false,
+        };
+        progression.on_lock(t_spin, 0);
+        progression.on_lock(clear(3), 0);
+        progression.on_lock(clear(3), 0);
+        assert_eq!(progression.level(), 3);
+    }
+}
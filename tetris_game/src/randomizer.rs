@@ -0,0 +1,202 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::random::GameRng;
+use crate::tetromino::Shape;
+
+/// The algorithms available to generate the sequence of tetromino shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomizerName {
+    /// Each shape is picked independently with the same probability.
+    Uniform,
+    /// Guideline "random generator": all 7 shapes are dealt in a shuffled
+    /// bag, and a new bag is shuffled when the current one is empty.
+    SevenBag,
+    /// NES: roll once, and reroll once if the shape repeats the previous one.
+    Nes,
+    /// TGM: avoid the 4 most recent shapes, giving up after 6 rolls.
+    Tgm,
+}
+
+/// A generator of tetromino shapes.
+pub trait Randomizer {
+    fn next_shape(&mut self, rng: &mut GameRng) -> Shape;
+}
+
+pub fn create_randomizer(name: RandomizerName) -> Box<dyn Randomizer> {
+    match name {
+        RandomizerName::Uniform => Box::new(UniformRandomizer),
+        RandomizerName::SevenBag => Box::new(SevenBagRandomizer::new()),
+        RandomizerName::Nes => Box::new(NesRandomizer::new()),
+        RandomizerName::Tgm => Box::new(TgmRandomizer::new()),
+    }
+}
+
+fn pick_uniform(rng: &mut GameRng) -> Shape {
+    Shape::ALL[rng.gen_range(0..Shape::ALL.len())]
+}
+
+pub struct UniformRandomizer;
+
+impl Randomizer for UniformRandomizer {
+    fn next_shape(&mut self, rng: &mut GameRng) -> Shape {
+        pick_uniform(rng)
+    }
+}
+
+pub struct SevenBagRandomizer {
+    bag: Vec<Shape>,
+}
+
+impl SevenBagRandomizer {
+    pub fn new() -> Self {
+        Self { bag: Vec::new() }
+    }
+}
+
+impl Randomizer for SevenBagRandomizer {
+    fn next_shape(&mut self, rng: &mut GameRng) -> Shape {
+        if self.bag.is_empty() {
+            self.bag = Shape::ALL.to_vec();
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().expect("The bag should never be empty")
+    }
+}
+
+pub struct NesRandomizer {
+    previous: Option<Shape>,
+}
+
+impl NesRandomizer {
+    pub fn new() -> Self {
+        Self { previous: None }
+    }
+}
+
+impl Randomizer for NesRandomizer {
+    fn next_shape(&mut self, rng: &mut GameRng) -> Shape {
+        // The first roll has 8 outcomes: the 8th one ("none") also triggers a reroll.
+        let n = rng.gen_range(0..=Shape::ALL.len());
+        let shape = match Shape::ALL.get(n) {
+            Some(&shape) if Some(shape) != self.previous => shape,
+            _ => pick_uniform(rng),
+        };
+        self.previous = Some(shape);
+        shape
+    }
+}
+
+pub struct TgmRandomizer {
+    history: [Shape; 4],
+    is_first: bool,
+}
+
+impl TgmRandomizer {
+    const N_ROLLS: usize = 6;
+
+    pub fn new() -> Self {
+        Self {
+            history: [Shape::Z, Shape::S, Shape::S, Shape::Z],
+            is_first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn next_shape(&mut self, rng: &mut GameRng) -> Shape {
+        let shape = if self.is_first {
+            // The first piece is never an S, Z or O, which would force an overhang.
+            self.is_first = false;
+            *[Shape::I, Shape::J, Shape::L, Shape::T]
+                .choose(rng)
+                .expect("Candidates should not be empty")
+        } else {
+            let mut shape = pick_uniform(rng);
+            for _ in 1..Self::N_ROLLS {
+                if !self.history.contains(&shape) {
+                    break;
+                }
+                shape = pick_uniform(rng);
+            }
+            shape
+        };
+        self.history.rotate_right(1);
+        self.history[0] = shape;
+        shape
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::random::create_rng;
+
+    fn generate(name: RandomizerName, seed: u64, n: usize) -> Vec<Shape> {
+        let mut rng = create_rng(Some(seed));
+        let mut randomizer = create_randomizer(name);
+        (0..n).map(|_| randomizer.next_shape(&mut rng)).collect()
+    }
+
+    #[test]
+    fn same_seed_same_shapes() {
+        for name in [
+            RandomizerName::Uniform,
+            RandomizerName::SevenBag,
+            RandomizerName::Nes,
+            RandomizerName::Tgm,
+        ] {
+            assert_eq!(generate(name, 42, 100), generate(name, 42, 100));
+        }
+    }
+
+    #[test]
+    fn uniform_picks_every_shape_equally() {
+        let n = 7000;
+        let shapes = generate(RandomizerName::Uniform, 42, n);
+        for shape in Shape::ALL {
+            let count = shapes.iter().filter(|&&s| s == shape).count();
+            // Expected 1000 each, with a standard deviation of about 30.
+            assert!((900..1100).contains(&count), "{:?}: {}", shape, count);
+        }
+    }
+
+    #[test]
+    fn seven_bag_deals_each_shape_once_per_bag() {
+        let shapes = generate(RandomizerName::SevenBag, 42, 7 * 20);
+        for bag in shapes.chunks(7) {
+            let distinct: HashSet<_> = bag.iter().collect();
+            assert_eq!(distinct.len(), 7);
+        }
+    }
+
+    #[test]
+    fn nes_repeats_less_often_than_uniform() {
+        let count_repeats = |shapes: &[Shape]| shapes.windows(2).filter(|w| w[0] == w[1]).count();
+        let n = 7000;
+        let nes_repeats = count_repeats(&generate(RandomizerName::Nes, 42, n));
+        let uniform_repeats = count_repeats(&generate(RandomizerName::Uniform, 42, n));
+        // Expected repeat rates: 1/28 for NES vs. 1/7 for uniform.
+        assert!(nes_repeats * 2 < uniform_repeats);
+    }
+
+    #[test]
+    fn tgm_first_shape_is_never_s_z_or_o() {
+        for seed in 0..100 {
+            let first = generate(RandomizerName::Tgm, seed, 1)[0];
+            assert!(![Shape::S, Shape::Z, Shape::O].contains(&first));
+        }
+    }
+
+    #[test]
+    fn tgm_rarely_repeats_recent_shapes() {
+        let shapes = generate(RandomizerName::Tgm, 42, 7000);
+        let n_repeats = shapes.windows(2).filter(|w| w[0] == w[1]).count();
+        // A shape repeats only if all 6 rolls hit the history: (4/7)^6 < 4%.
+        assert!(n_repeats < shapes.len() / 25);
+    }
+}
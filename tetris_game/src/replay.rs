@@ -0,0 +1,107 @@
+use crate::common::{Button, GamePad};
+use crate::mode::GameOptions;
+use crate::random::GameRng;
+
+/// The input of a game loop: the buttons held down with the number of loops
+/// they have been held, and the cheat code typed if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Input {
+    held_loops: Vec<(Button, u32)>,
+    cheat_code: Option<char>,
+}
+
+/// A recording of a game: the options and the random number generator the
+/// game started with, and the input of every game loop. Replaying the input
+/// with the same settings plays the same game again.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    options: GameOptions,
+    rng: GameRng,
+    inputs: Vec<Input>,
+}
+
+impl Replay {
+    pub fn new(options: GameOptions, rng: GameRng) -> Self {
+        Self {
+            options,
+            rng,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn options(&self) -> GameOptions {
+        self.options
+    }
+
+    /// The random number generator to start the replayed game with.
+    pub fn rng(&self) -> GameRng {
+        self.rng.clone()
+    }
+
+    /// Record the input of the next game loop.
+    pub fn record(&mut self, pad: &dyn GamePad) {
+        let held_loops = Button::ALL
+            .into_iter()
+            .map(|button| (button, pad.held_loops(button)))
+            .filter(|&(_, held_loops)| held_loops > 0)
+            .collect();
+        self.inputs.push(Input {
+            held_loops,
+            cheat_code: pad.cheat_code(),
+        });
+    }
+
+    /// A game pad replaying the input of the game loop at `index`, or `None`
+    /// at the end of the replay.
+    pub fn pad(&self, index: usize) -> Option<ReplayPad<'_>> {
+        self.inputs.get(index).map(|input| ReplayPad { input })
+    }
+}
+
+/// A game pad replaying the recorded input of a game loop.
+pub struct ReplayPad<'a> {
+    input: &'a Input,
+}
+
+impl<'a> GamePad for ReplayPad<'a> {
+    fn held_loops(&self, button: Button) -> u32 {
+        self.input
+            .held_loops
+            .iter()
+            .find(|(held_button, _)| *held_button == button)
+            .map_or(0, |&(_, held_loops)| held_loops)
+    }
+
+    fn cheat_code(&self) -> Option<char> {
+        self.input.cheat_code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TestGamePad;
+    use crate::random::create_rng;
+
+    #[test]
+    fn record_and_replay() {
+        let mut replay = Replay::new(GameOptions::default(), create_rng(Some(0)));
+        replay.record(&TestGamePad {
+            held_loops: vec![(Button::Left, 1), (Button::A, 7)],
+            cheat_code: Some('x'),
+        });
+        replay.record(&TestGamePad::default());
+
+        let pad = replay.pad(0).unwrap();
+        assert_eq!(pad.held_loops(Button::Left), 1);
+        assert_eq!(pad.held_loops(Button::A), 7);
+        assert_eq!(pad.held_loops(Button::B), 0);
+        assert_eq!(pad.cheat_code(), Some('x'));
+
+        let pad = replay.pad(1).unwrap();
+        assert_eq!(pad.held_loops(Button::A), 0);
+        assert_eq!(pad.cheat_code(), None);
+
+        assert!(replay.pad(2).is_none());
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::Position;
+use crate::tetromino::{GameWorld, Shape};
+
+/// The rule sets available to rotate the tetrominoes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationSystemName {
+    /// Nintendo Rotation System (NES): no wall kicks.
+    Nintendo,
+    /// Arika Rotation System (TGM): kicks by one cell, with the center column rule.
+    Arika,
+    /// Super Rotation System (guideline): kick tables for JLSTZ and I.
+    Srs,
+}
+
+/// A rotation rule set: the orientations of each shape, and the wall kicks to
+/// try when a rotated tetromino does not fit.
+///
+/// Rotation states are numbered as the number of clockwise turns from the
+/// spawn orientation: 0 (spawn), 1 (R), 2 (reverse) and 3 (L).
+pub trait RotationSystem: Debug {
+    /// The relative positions of the bricks of the shape in a rotation state,
+    /// within the bounding box whose top-left corner is the tetromino position.
+    fn bricks(&self, shape: Shape, rotation: u8) -> [(i16, i16); 4];
+
+    /// The offsets to move the tetromino by, to test in order, when rotating it
+    /// from a rotation state to another at the given position. The first
+    /// offset is `(0, 0)` for a plain rotation; an empty list forbids the rotation.
+    fn kicks(
+        &self,
+        shape: Shape,
+        from: u8,
+        to: u8,
+        position: Position,
+        world: &dyn GameWorld,
+    ) -> Vec<(i16, i16)>;
+}
+
+mod arika;
+mod nintendo;
+mod srs;
+
+use arika::ArikaRotationSystem;
+use nintendo::NintendoRotationSystem;
+use srs::SuperRotationSystem;
+
+pub fn get_rotation_system(name: RotationSystemName) -> &'static dyn RotationSystem {
+    match name {
+        RotationSystemName::Nintendo => &NintendoRotationSystem,
+        RotationSystemName::Arika => &ArikaRotationSystem,
+        RotationSystemName::Srs => &SuperRotationSystem,
+    }
+}
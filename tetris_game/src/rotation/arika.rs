@@ -0,0 +1,155 @@
+use crate::common::Position;
+use crate::tetromino::{get_bricks, GameWorld, Shape};
+
+use super::RotationSystem;
+
+/// The Arika Rotation System, as in the Tetris The Grand Master series.
+///
+/// Shapes spawn with their flat side pointing up and stay aligned to the
+/// bottom of their box when rotating. A rotation that does not fit is retried
+/// one cell to the right, then one cell to the left, except for the I (which
+/// never kicks) and under the center column rule for J, L and T.
+#[derive(Debug)]
+pub struct ArikaRotationSystem;
+
+impl RotationSystem for ArikaRotationSystem {
+    fn bricks(&self, shape: Shape, rotation: u8) -> [(i16, i16); 4] {
+        let rotation = rotation as usize % 4;
+        match shape {
+            Shape::I => I_BRICKS[rotation % 2],
+            Shape::O => [(1, 1), (2, 1), (1, 2), (2, 2)],
+            Shape::T => T_BRICKS[rotation],
+            Shape::J => J_BRICKS[rotation],
+            Shape::L => L_BRICKS[rotation],
+            Shape::S => S_BRICKS[rotation % 2],
+            Shape::Z => Z_BRICKS[rotation % 2],
+        }
+    }
+
+    fn kicks(
+        &self,
+        shape: Shape,
+        _from: u8,
+        to: u8,
+        position: Position,
+        world: &dyn GameWorld,
+    ) -> Vec<(i16, i16)> {
+        const NO_KICK: (i16, i16) = (0, 0);
+        const KICK_RIGHT: (i16, i16) = (1, 0);
+        const KICK_LEFT: (i16, i16) = (-1, 0);
+        match shape {
+            Shape::I | Shape::O => vec![NO_KICK],
+            Shape::J | Shape::L | Shape::T
+                if blocked_in_center_column(shape, to, position, world) =>
+            {
+                vec![NO_KICK]
+            }
+            _ => vec![NO_KICK, KICK_RIGHT, KICK_LEFT],
+        }
+    }
+}
+
+/// The center column rule: scanning the bricks of the rotated tetromino in
+/// reading order (from top to bottom, then from left to right), if the first
+/// blocked brick is in the center column of the box, the rotation cannot kick.
+fn blocked_in_center_column(
+    shape: Shape,
+    rotation: u8,
+    position: Position,
+    world: &dyn GameWorld,
+) -> bool {
+    let mut bricks = get_bricks(&ArikaRotationSystem, shape, position, rotation);
+    bricks.sort_by_key(|pos| (pos.xy().1, pos.xy().0));
+    let (center_x, _) = position.updated((1, 0)).xy();
+    bricks
+        .into_iter()
+        .find(|brick| !world.is_free(&[*brick]))
+        .map(|brick| brick.xy().0 == center_x)
+        .unwrap_or(false)
+}
+
+// Orientations of the shapes in a 3x3 box (4x4 for I), aligned to the bottom.
+const I_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(0, 1), (1, 1), (2, 1), (3, 1)],
+    [(2, 0), (2, 1), (2, 2), (2, 3)],
+];
+const T_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (1, 2)],
+    [(1, 0), (0, 1), (1, 1), (1, 2)],
+    [(1, 1), (0, 2), (1, 2), (2, 2)],
+    [(1, 0), (1, 1), (2, 1), (1, 2)],
+];
+const J_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (2, 2)],
+    [(1, 0), (1, 1), (0, 2), (1, 2)],
+    [(0, 1), (0, 2), (1, 2), (2, 2)],
+    [(1, 0), (2, 0), (1, 1), (1, 2)],
+];
+const L_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (0, 2)],
+    [(0, 0), (1, 0), (1, 1), (1, 2)],
+    [(2, 1), (0, 2), (1, 2), (2, 2)],
+    [(1, 0), (1, 1), (1, 2), (2, 2)],
+];
+const S_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(1, 1), (2, 1), (0, 2), (1, 2)],
+    [(0, 0), (0, 1), (1, 1), (1, 2)],
+];
+const Z_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(0, 1), (1, 1), (1, 2), (2, 2)],
+    [(2, 0), (1, 1), (2, 1), (1, 2)],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playfield::PlayField;
+    use crate::tetromino::Tetromino;
+    use crate::Color;
+
+    #[test]
+    fn shapes_are_bottom_aligned() {
+        for shape in [Shape::T, Shape::J, Shape::L, Shape::S, Shape::Z, Shape::O] {
+            for rotation in 0..4 {
+                let bricks = ArikaRotationSystem.bricks(shape, rotation);
+                assert!(bricks.iter().any(|&(_, y)| y == 2), "{:?}", shape);
+            }
+        }
+    }
+
+    #[test]
+    fn kicks_right_off_the_left_wall() {
+        // A T pointing right, against the left wall, is kicked to the right.
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(Shape::T, Position::new(3, 5), &ArikaRotationSystem);
+        for _ in 0..3 {
+            assert!(tetromino.rotate_right(&field));
+        }
+        while tetromino.move_towards((-1, 0), &field) {}
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(tetromino.position(), Position::new(0, 5));
+        assert_eq!(tetromino.rotation(), 0);
+    }
+
+    #[test]
+    fn center_column_rule_prevents_kicks() {
+        // A T pointing down, with a brick right above its center: rotating to
+        // R would hit it first in the center column, so it cannot kick.
+        let mut field = PlayField::new(10, 20, 0);
+        field.fill_space(&[Position::new(4, 5)], Color::Gray);
+        let mut tetromino = Tetromino::new(Shape::T, Position::new(3, 5), &ArikaRotationSystem);
+        assert!(!tetromino.rotate_right(&field));
+        assert_eq!(tetromino.rotation(), 0);
+
+        // A T pointing left, with a brick at the bottom-right corner: rotating
+        // to 2 first hits it outside of the center column, so it can kick
+        // (to the left, as the right is blocked as well).
+        let mut field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(Shape::T, Position::new(3, 5), &ArikaRotationSystem);
+        assert!(tetromino.rotate_right(&field));
+        field.fill_space(&[Position::new(5, 7)], Color::Gray);
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(tetromino.position(), Position::new(2, 5));
+        assert_eq!(tetromino.rotation(), 2);
+    }
+}
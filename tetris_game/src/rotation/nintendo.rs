@@ -0,0 +1,112 @@
+use crate::common::Position;
+use crate::tetromino::{GameWorld, Shape};
+
+use super::RotationSystem;
+
+/// The Nintendo Rotation System, as in NES Tetris.
+///
+/// T, J and L spawn with their flat side pointing up, S, Z and I only have
+/// two orientations, and rotations never kick.
+#[derive(Debug)]
+pub struct NintendoRotationSystem;
+
+impl RotationSystem for NintendoRotationSystem {
+    fn bricks(&self, shape: Shape, rotation: u8) -> [(i16, i16); 4] {
+        let rotation = rotation as usize % 4;
+        match shape {
+            Shape::I => I_BRICKS[rotation % 2],
+            Shape::O => [(1, 1), (2, 1), (1, 2), (2, 2)],
+            Shape::T => T_BRICKS[rotation],
+            Shape::J => J_BRICKS[rotation],
+            Shape::L => L_BRICKS[rotation],
+            Shape::S => S_BRICKS[rotation % 2],
+            Shape::Z => Z_BRICKS[rotation % 2],
+        }
+    }
+
+    fn kicks(
+        &self,
+        _shape: Shape,
+        _from: u8,
+        _to: u8,
+        _position: Position,
+        _world: &dyn GameWorld,
+    ) -> Vec<(i16, i16)> {
+        vec![(0, 0)]
+    }
+}
+
+// Orientations of the shapes rotating around the center brick of a 3x3 box
+// (or the brick right below the center of a 4x4 box for I).
+const I_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(0, 2), (1, 2), (2, 2), (3, 2)],
+    [(2, 0), (2, 1), (2, 2), (2, 3)],
+];
+const T_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (1, 2)],
+    [(1, 0), (0, 1), (1, 1), (1, 2)],
+    [(1, 0), (0, 1), (1, 1), (2, 1)],
+    [(1, 0), (1, 1), (2, 1), (1, 2)],
+];
+const J_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (2, 2)],
+    [(1, 0), (1, 1), (0, 2), (1, 2)],
+    [(0, 0), (0, 1), (1, 1), (2, 1)],
+    [(1, 0), (2, 0), (1, 1), (1, 2)],
+];
+const L_BRICKS: [[(i16, i16); 4]; 4] = [
+    [(0, 1), (1, 1), (2, 1), (0, 2)],
+    [(0, 0), (1, 0), (1, 1), (1, 2)],
+    [(2, 0), (0, 1), (1, 1), (2, 1)],
+    [(1, 0), (1, 1), (1, 2), (2, 2)],
+];
+const S_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(1, 1), (2, 1), (0, 2), (1, 2)],
+    [(1, 0), (1, 1), (2, 1), (2, 2)],
+];
+const Z_BRICKS: [[(i16, i16); 4]; 2] = [
+    [(0, 1), (1, 1), (1, 2), (2, 2)],
+    [(2, 0), (1, 1), (2, 1), (1, 2)],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playfield::PlayField;
+    use crate::tetromino::Tetromino;
+
+    #[test]
+    fn jlt_rotate_around_their_center_brick() {
+        for shape in [Shape::J, Shape::L, Shape::T] {
+            for rotation in 0..4 {
+                assert!(NintendoRotationSystem
+                    .bricks(shape, rotation)
+                    .contains(&(1, 1)));
+            }
+        }
+    }
+
+    #[test]
+    fn szi_have_two_orientations() {
+        for shape in [Shape::S, Shape::Z, Shape::I] {
+            let system = NintendoRotationSystem;
+            assert_eq!(system.bricks(shape, 0), system.bricks(shape, 2));
+            assert_eq!(system.bricks(shape, 1), system.bricks(shape, 3));
+            assert_ne!(system.bricks(shape, 0), system.bricks(shape, 1));
+        }
+    }
+
+    #[test]
+    fn no_wall_kicks() {
+        // A T pointing right, against the left wall, cannot rotate.
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(Shape::T, Position::new(3, 5), &NintendoRotationSystem);
+        for _ in 0..3 {
+            assert!(tetromino.rotate_right(&field));
+        }
+        while tetromino.move_towards((-1, 0), &field) {}
+        assert!(!tetromino.rotate_right(&field));
+        assert_eq!(tetromino.position(), Position::new(-1, 5));
+        assert_eq!(tetromino.rotation(), 3);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::common::Position;
+use crate::tetromino::{GameWorld, Shape};
+
+use super::RotationSystem;
+
+/// The Super Rotation System, used by the Tetris guideline.
+///
+/// All shapes spawn horizontally with their flat side pointing down, and
+/// rotate around the true center of their bounding box.
+#[derive(Debug)]
+pub struct SuperRotationSystem;
+
+impl SuperRotationSystem {
+    /// The relative positions of the bricks in the spawn orientation, within
+    /// the bounding box of the shape.
+    fn spawn_bricks(shape: Shape) -> [(i16, i16); 4] {
+        match shape {
+            Shape::I => [(0, 1), (1, 1), (2, 1), (3, 1)],
+            Shape::O => [(1, 0), (2, 0), (1, 1), (2, 1)],
+            Shape::T => [(1, 0), (0, 1), (1, 1), (2, 1)],
+            Shape::J => [(0, 0), (0, 1), (1, 1), (2, 1)],
+            Shape::L => [(2, 0), (0, 1), (1, 1), (2, 1)],
+            Shape::S => [(1, 0), (2, 0), (0, 1), (1, 1)],
+            Shape::Z => [(0, 0), (1, 0), (1, 1), (2, 1)],
+        }
+    }
+
+    /// The size of the (square) bounding box, the center of which is the
+    /// rotation center of the shape.
+    fn box_size(shape: Shape) -> i16 {
+        match shape {
+            Shape::I | Shape::O => 4,
+            _ => 3,
+        }
+    }
+}
+
+impl RotationSystem for SuperRotationSystem {
+    fn bricks(&self, shape: Shape, rotation: u8) -> [(i16, i16); 4] {
+        // The O shape looks the same whatever its rotation.
+        let n_times = if shape == Shape::O { 0 } else { rotation % 4 };
+        let size = Self::box_size(shape);
+        // Rotate right the relative positions of the bricks in the bounding box n times
+        Self::spawn_bricks(shape).map(|(mut x, mut y)| {
+            for _ in 0..n_times {
+                (x, y) = (size - 1 - y, x);
+            }
+            (x, y)
+        })
+    }
+
+    fn kicks(
+        &self,
+        shape: Shape,
+        from: u8,
+        to: u8,
+        _position: Position,
+        _world: &dyn GameWorld,
+    ) -> Vec<(i16, i16)> {
+        let index = match (from % 4, to % 4) {
+            (0, 1) => 0,
+            (1, 0) => 1,
+            (1, 2) => 2,
+            (2, 1) => 3,
+            (2, 3) => 4,
+            (3, 2) => 5,
+            (3, 0) => 6,
+            (0, 3) => 7,
+            _ => return vec![(0, 0)],
+        };
+        match shape {
+            Shape::I => I_KICKS[index].to_vec(),
+            Shape::O => vec![(0, 0)],
+            _ => JLSTZ_KICKS[index].to_vec(),
+        }
+    }
+}
+
+// SRS wall kick data for J, L, S, T and Z shapes, in the order of
+// 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L.
+// Note: As y points downwards in the play field, these are the published
+// offsets with their y components negated.
+const JLSTZ_KICKS: [[(i16, i16); 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+];
+
+// SRS wall kick data for the I shape, in the same order and convention as above.
+const I_KICKS: [[(i16, i16); 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
+    [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
+    [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playfield::PlayField;
+    use crate::tetromino::{get_bricks, Tetromino};
+
+    /// A world in which only the given bricks are free.
+    struct OnlyFree(Vec<Position>);
+
+    impl GameWorld for OnlyFree {
+        fn is_free(&self, positions: &[Position]) -> bool {
+            positions.iter().all(|pos| self.0.contains(pos))
+        }
+    }
+
+    fn positions(xys: &[(i16, i16)]) -> Vec<Position> {
+        xys.iter().map(|&(x, y)| Position::new(x, y)).collect()
+    }
+
+    fn sorted(mut bricks: Vec<Position>) -> Vec<Position> {
+        bricks.sort_by_key(|pos| (pos.xy().1, pos.xy().0));
+        bricks
+    }
+
+    /// Create a tetromino in the given rotation state, by rotating it in an empty field.
+    fn rotated_tetromino(shape: Shape, position: Position, rotation: u8) -> Tetromino {
+        let field = PlayField::new(40, 40, 0);
+        let mut tetromino = Tetromino::new(shape, position, &SuperRotationSystem);
+        for _ in 0..rotation {
+            assert!(tetromino.rotate_right(&field));
+        }
+        assert_eq!(tetromino.position(), position);
+        tetromino
+    }
+
+    #[test]
+    fn spawn_orientations() {
+        let expected = [
+            (Shape::I, vec![(3, 1), (4, 1), (5, 1), (6, 1)]),
+            (Shape::O, vec![(4, 0), (5, 0), (4, 1), (5, 1)]),
+            (Shape::T, vec![(4, 0), (3, 1), (4, 1), (5, 1)]),
+            (Shape::J, vec![(3, 0), (3, 1), (4, 1), (5, 1)]),
+            (Shape::L, vec![(5, 0), (3, 1), (4, 1), (5, 1)]),
+            (Shape::S, vec![(4, 0), (5, 0), (3, 1), (4, 1)]),
+            (Shape::Z, vec![(3, 0), (4, 0), (4, 1), (5, 1)]),
+        ];
+        for (shape, xys) in expected {
+            let tetromino = Tetromino::new(shape, Position::new(3, 0), &SuperRotationSystem);
+            assert_eq!(
+                sorted(tetromino.bricks().to_vec()),
+                sorted(positions(&xys)),
+                "{:?}",
+                shape
+            );
+        }
+    }
+
+    #[test]
+    fn i_rotates_around_the_center_of_its_box() {
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(Shape::I, Position::new(3, 5), &SuperRotationSystem);
+        let expected_bricks = [
+            vec![(5, 5), (5, 6), (5, 7), (5, 8)],
+            vec![(3, 7), (4, 7), (5, 7), (6, 7)],
+            vec![(4, 5), (4, 6), (4, 7), (4, 8)],
+            vec![(3, 6), (4, 6), (5, 6), (6, 6)],
+        ];
+        for xys in expected_bricks {
+            assert!(tetromino.rotate_right(&field));
+            assert_eq!(sorted(tetromino.bricks().to_vec()), positions(&xys));
+        }
+    }
+
+    #[test]
+    fn o_does_not_move_when_rotated() {
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(Shape::O, Position::new(3, 5), &SuperRotationSystem);
+        let spawn_bricks = tetromino.bricks().to_vec();
+        for _ in 0..4 {
+            assert!(tetromino.rotate_right(&field));
+            assert_eq!(tetromino.bricks(), spawn_bricks.as_slice());
+        }
+    }
+
+    /// Kicks of a rotation from a state to another, as (from, to, offsets).
+    type Kicks = (u8, u8, [(i16, i16); 5]);
+
+    /// Kick data as published in the SRS specification, with y pointing upwards.
+    const PUBLISHED_JLSTZ_KICKS: [Kicks; 8] = [
+        (0, 1, [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)]),
+        (1, 0, [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)]),
+        (1, 2, [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)]),
+        (2, 1, [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)]),
+        (2, 3, [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)]),
+        (3, 2, [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)]),
+        (3, 0, [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)]),
+        (0, 3, [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)]),
+    ];
+
+    const PUBLISHED_I_KICKS: [Kicks; 8] = [
+        (0, 1, [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)]),
+        (1, 0, [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)]),
+        (1, 2, [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)]),
+        (2, 1, [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)]),
+        (2, 3, [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)]),
+        (3, 2, [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)]),
+        (3, 0, [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)]),
+        (0, 3, [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)]),
+    ];
+
+    #[test]
+    fn kicks_match_published_data() {
+        let shapes_and_kicks = [
+            (Shape::I, &PUBLISHED_I_KICKS),
+            (Shape::T, &PUBLISHED_JLSTZ_KICKS),
+            (Shape::J, &PUBLISHED_JLSTZ_KICKS),
+            (Shape::L, &PUBLISHED_JLSTZ_KICKS),
+            (Shape::S, &PUBLISHED_JLSTZ_KICKS),
+            (Shape::Z, &PUBLISHED_JLSTZ_KICKS),
+        ];
+        let origin = Position::new(10, 10);
+        for (shape, published_kicks) in shapes_and_kicks {
+            for &(from, to, kicks) in published_kicks {
+                for (dx, dy) in kicks {
+                    // Only the position kicked by (dx, dy) is free: the rotation
+                    // must succeed by trying this kick.
+                    let kicked_position = origin.updated((dx, -dy));
+                    let world =
+                        OnlyFree(get_bricks(&SuperRotationSystem, shape, kicked_position, to));
+                    let mut tetromino = rotated_tetromino(shape, origin, from);
+                    let is_rotated = if to == (from + 1) % 4 {
+                        tetromino.rotate_right(&world)
+                    } else {
+                        tetromino.rotate_left(&world)
+                    };
+                    assert!(
+                        is_rotated,
+                        "{:?} {}->{} kick ({}, {})",
+                        shape, from, to, dx, dy
+                    );
+                    assert_eq!(tetromino.position(), kicked_position);
+                    assert_eq!(tetromino.rotation(), to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotation_fails_if_no_kick_fits() {
+        let world = OnlyFree(vec![]);
+        let mut tetromino = Tetromino::new(Shape::T, Position::new(3, 5), &SuperRotationSystem);
+        assert!(!tetromino.rotate_right(&world));
+        assert_eq!(tetromino.rotation(), 0);
+    }
+
+    #[test]
+    fn rotate_180_does_not_kick() {
+        let mut tetromino = rotated_tetromino(Shape::T, Position::new(3, 5), 0);
+        let field = PlayField::new(10, 20, 0);
+        assert!(tetromino.rotate_180(&field));
+        assert_eq!(tetromino.position(), Position::new(3, 5));
+        assert_eq!(tetromino.rotation(), 2);
+
+        let world = OnlyFree(tetromino.bricks().to_vec());
+        assert!(!tetromino.rotate_180(&world));
+        assert_eq!(tetromino.rotation(), 2);
+    }
+
+    #[test]
+    fn i_kicks_off_the_left_wall() {
+        // A vertical I (state R) standing against the left wall.
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = rotated_tetromino(Shape::I, Position::new(-2, 5), 1);
+        assert_eq!(tetromino.bricks()[0].xy(), (0, 5));
+
+        // R->2 would put the I out of the play field: it is kicked by (+2, 0).
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(
+            sorted(tetromino.bricks().to_vec()),
+            positions(&[(0, 7), (1, 7), (2, 7), (3, 7)])
+        );
+    }
+}
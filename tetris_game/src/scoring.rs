@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+
+use crate::conf::TetrisSettings;
+use crate::tspin::TSpin;
+
+/// The rule sets available to score the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringName {
+    /// A flat score per number of rows cleared, from `scores_for_rows_destroyed`.
+    Simple,
+    /// Guideline: level multiplier, drop points, combos and back-to-back bonus.
+    Guideline,
+    /// NES: 40, 100, 300 and 1200 points per clear, multiplied by level + 1.
+    Nes,
+}
+
+/// What happened when a tetromino was locked into the play field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clear {
+    pub n_rows: u32,
+    pub t_spin: TSpin,
+    /// Whether the clear leaves the play field empty.
+    pub is_perfect_clear: bool,
+}
+
+impl Clear {
+    /// Whether the clear keeps the back-to-back chain going.
+    pub fn is_difficult(&self) -> bool {
+        self.n_rows >= 4 || (self.n_rows > 0 && self.t_spin != TSpin::None)
+    }
+
+    /// The lines of text announcing the clear to the player, e.g. "T-SPIN" and
+    /// "DOUBLE". Empty if there is nothing worth announcing.
+    pub fn labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        match self.t_spin {
+            TSpin::None => {}
+            TSpin::Mini => labels.push("T-SPIN MINI"),
+            TSpin::Full => labels.push("T-SPIN"),
+        }
+        match self.n_rows {
+            0 => {}
+            1 => labels.push("SINGLE"),
+            2 => labels.push("DOUBLE"),
+            3 => labels.push("TRIPLE"),
+            _ => labels.push("TETRIS"),
+        }
+        if self.is_perfect_clear {
+            // Also known as "PERFECT CLEAR", which is too wide for the side panel.
+            labels.push("ALL CLEAR");
+        }
+        labels
+    }
+}
+
+/// A scoring rule set, keeping track of the chains (combos, back-to-back)
+/// across the locked tetrominoes.
+pub trait Scoring {
+    /// Score for soft-dropping the active tetromino by some rows.
+    fn soft_drop(&mut self, n_rows: u32) -> u32;
+
+    /// Score for hard-dropping the active tetromino by some rows.
+    fn hard_drop(&mut self, n_rows: u32) -> u32;
+
+    /// Score for locking a tetromino, at the given level (starting from 0).
+    fn lock(&mut self, clear: Clear, level: u8) -> u32;
+}
+
+pub fn create_scoring(settings: &TetrisSettings) -> Box<dyn Scoring> {
+    match settings.scoring {
+        ScoringName::Simple => Box::new(SimpleScoring {
+            scores_for_rows_destroyed: settings.scores_for_rows_destroyed,
+        }),
+        ScoringName::Guideline => Box::new(GuidelineScoring::new()),
+        ScoringName::Nes => Box::new(NesScoring),
+    }
+}
+
+/// Look up the score of a clear in a table indexed by `n_rows - 1`, the last
+/// entry being used for any larger clear.
+fn lookup(scores: &[u32], n_rows: u32) -> u32 {
+    if n_rows == 0 || scores.is_empty() {
+        return 0;
+    }
+    let index = (scores.len() - 1).min(n_rows as usize - 1);
+    scores[index]
+}
+
+pub struct SimpleScoring {
+    scores_for_rows_destroyed: [u32; 4],
+}
+
+impl Scoring for SimpleScoring {
+    fn soft_drop(&mut self, n_rows: u32) -> u32 {
+        n_rows
+    }
+
+    fn hard_drop(&mut self, _n_rows: u32) -> u32 {
+        0
+    }
+
+    fn lock(&mut self, clear: Clear, _level: u8) -> u32 {
+        lookup(&self.scores_for_rows_destroyed, clear.n_rows)
+    }
+}
+
+pub struct GuidelineScoring {
+    /// Number of consecutive clears minus 1, or `None` if the last lock did not clear any row.
+    combo: Option<u32>,
+    /// Whether the last clear was a difficult one.
+    is_back_to_back: bool,
+}
+
+impl GuidelineScoring {
+    // Scores indexed by the number of rows cleared, from 0.
+    const SCORES: [u32; 5] = [0, 100, 300, 500, 800];
+    const T_SPIN_MINI_SCORES: [u32; 3] = [100, 200, 400];
+    const T_SPIN_SCORES: [u32; 4] = [400, 800, 1200, 1600];
+    const COMBO_SCORE: u32 = 50;
+    // Perfect clear bonuses indexed by `n_rows - 1`, on top of the clear score.
+    const PERFECT_CLEAR_SCORES: [u32; 4] = [800, 1200, 1800, 2000];
+    const BACK_TO_BACK_TETRIS_PERFECT_CLEAR_SCORE: u32 = 3200;
+
+    pub fn new() -> Self {
+        Self {
+            combo: None,
+            is_back_to_back: false,
+        }
+    }
+}
+
+impl Scoring for GuidelineScoring {
+    fn soft_drop(&mut self, n_rows: u32) -> u32 {
+        n_rows
+    }
+
+    fn hard_drop(&mut self, n_rows: u32) -> u32 {
+        n_rows * 2
+    }
+
+    fn lock(&mut self, clear: Clear, level: u8) -> u32 {
+        // Guideline levels start from 1.
+        let multiplier = u32::from(level) + 1;
+        let scores: &[u32] = match clear.t_spin {
+            TSpin::None => &Self::SCORES,
+            TSpin::Mini => &Self::T_SPIN_MINI_SCORES,
+            TSpin::Full => &Self::T_SPIN_SCORES,
+        };
+        // Tables are indexed from 0 row here, hence the + 1.
+        let mut score = lookup(scores, clear.n_rows + 1) * multiplier;
+        if clear.n_rows == 0 {
+            // The combo is broken, but the back-to-back chain is not.
+            self.combo = None;
+            return score;
+        }
+
+        let was_back_to_back = self.is_back_to_back;
+        if clear.is_difficult() {
+            if was_back_to_back {
+                score = score * 3 / 2;
+            }
+            self.is_back_to_back = true;
+        } else {
+            self.is_back_to_back = false;
+        }
+
+        if clear.is_perfect_clear {
+            let bonus = if clear.n_rows >= 4 && was_back_to_back {
+                Self::BACK_TO_BACK_TETRIS_PERFECT_CLEAR_SCORE
+            } else {
+                lookup(&Self::PERFECT_CLEAR_SCORES, clear.n_rows)
+            };
+            score += bonus * multiplier;
+        }
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        self.combo = Some(combo);
+        score + Self::COMBO_SCORE * combo * multiplier
+    }
+}
+
+pub struct NesScoring;
+
+impl NesScoring {
+    const SCORES: [u32; 4] = [40, 100, 300, 1200];
+}
+
+impl Scoring for NesScoring {
+    fn soft_drop(&mut self, n_rows: u32) -> u32 {
+        n_rows
+    }
+
+    fn hard_drop(&mut self, _n_rows: u32) -> u32 {
+        0
+    }
+
+    fn lock(&mut self, clear: Clear, level: u8) -> u32 {
+        lookup(&Self::SCORES, clear.n_rows) * (u32::from(level) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scoring(name: ScoringName) -> Box<dyn Scoring> {
+        let settings = TetrisSettings {
+            scoring: name,
+            scores_for_rows_destroyed: [10, 30, 50, 100],
+            ..Default::default()
+        };
+        create_scoring(&settings)
+    }
+
+    fn clear(n_rows: u32) -> Clear {
+        Clear {
+            n_rows,
+            t_spin: TSpin::None,
+            is_perfect_clear: false,
+        }
+    }
+
+    fn t_spin(n_rows: u32, t_spin: TSpin) -> Clear {
+        Clear {
+            n_rows,
+            t_spin,
+            is_perfect_clear: false,
+        }
+    }
+
+    fn perfect_clear(n_rows: u32) -> Clear {
+        Clear {
+            n_rows,
+            t_spin: TSpin::None,
+            is_perfect_clear: true,
+        }
+    }
+
+    #[test]
+    fn simple_uses_the_score_table() {
+        let mut scoring = scoring(ScoringName::Simple);
+        assert_eq!(scoring.lock(clear(0), 5), 0);
+        assert_eq!(scoring.lock(clear(1), 5), 10);
+        assert_eq!(scoring.lock(clear(4), 5), 100);
+        assert_eq!(scoring.soft_drop(3), 3);
+        assert_eq!(scoring.hard_drop(3), 0);
+    }
+
+    #[test]
+    fn guideline_drop_scores() {
+        let mut scoring = scoring(ScoringName::Guideline);
+        assert_eq!(scoring.soft_drop(5), 5);
+        assert_eq!(scoring.hard_drop(5), 10);
+    }
+
+    #[test]
+    fn guideline_level_multiplier() {
+        let expected = [(1, 100), (2, 300), (3, 500), (4, 800)];
+        for (n_rows, score) in expected {
+            // Level 0 is guideline level 1, level 2 is guideline level 3.
+            assert_eq!(
+                scoring(ScoringName::Guideline).lock(clear(n_rows), 0),
+                score
+            );
+            assert_eq!(
+                scoring(ScoringName::Guideline).lock(clear(n_rows), 2),
+                score * 3
+            );
+        }
+    }
+
+    #[test]
+    fn guideline_combo() {
+        let mut scoring = scoring(ScoringName::Guideline);
+        assert_eq!(scoring.lock(clear(1), 0), 100);
+        assert_eq!(scoring.lock(clear(1), 0), 100 + 50);
+        assert_eq!(scoring.lock(clear(2), 0), 300 + 100);
+        assert_eq!(scoring.lock(clear(1), 1), (100 + 150) * 2);
+        // A lock without clear breaks the combo.
+        assert_eq!(scoring.lock(clear(0), 0), 0);
+        assert_eq!(scoring.lock(clear(1), 0), 100);
+    }
+
+    #[test]
+    fn guideline_back_to_back() {
+        let mut scoring = scoring(ScoringName::Guideline);
+        assert_eq!(scoring.lock(clear(4), 0), 800);
+        // Not clearing any row does not break the back-to-back chain.
+        assert_eq!(scoring.lock(clear(0), 0), 0);
+        assert_eq!(scoring.lock(clear(4), 0), 1200);
+        assert_eq!(scoring.lock(clear(0), 0), 0);
+        // An easy clear breaks it.
+        assert_eq!(scoring.lock(clear(1), 0), 100);
+        assert_eq!(scoring.lock(clear(0), 0), 0);
+        assert_eq!(scoring.lock(clear(4), 0), 800);
+    }
+
+    #[test]
+    fn guideline_t_spins() {
+        let expected = [
+            (TSpin::Mini, 0, 100),
+            (TSpin::Mini, 1, 200),
+            (TSpin::Mini, 2, 400),
+            (TSpin::Full, 0, 400),
+            (TSpin::Full, 1, 800),
+            (TSpin::Full, 2, 1200),
+            (TSpin::Full, 3, 1600),
+        ];
+        for (spin, n_rows, score) in expected {
+            let mut scoring = scoring(ScoringName::Guideline);
+            assert_eq!(scoring.lock(t_spin(n_rows, spin), 1), score * 2);
+        }
+    }
+
+    #[test]
+    fn guideline_t_spins_are_difficult() {
+        let mut scoring = scoring(ScoringName::Guideline);
+        assert_eq!(scoring.lock(t_spin(2, TSpin::Full), 0), 1200);
+        // A T-spin without clear neither breaks nor extends the chain.
+        assert_eq!(scoring.lock(t_spin(0, TSpin::Full), 0), 400);
+        assert_eq!(scoring.lock(clear(4), 0), 1200);
+        assert_eq!(scoring.lock(t_spin(1, TSpin::Mini), 0), 300 + 50);
+    }
+
+    #[test]
+    fn guideline_perfect_clears() {
+        let expected = [
+            (1, 100 + 800),
+            (2, 300 + 1200),
+            (3, 500 + 1800),
+            (4, 800 + 2000),
+        ];
+        for (n_rows, score) in expected {
+            let mut scoring = scoring(ScoringName::Guideline);
+            assert_eq!(scoring.lock(perfect_clear(n_rows), 1), score * 2);
+        }
+
+        let mut scoring = scoring(ScoringName::Guideline);
+        assert_eq!(scoring.lock(clear(4), 0), 800);
+        assert_eq!(scoring.lock(clear(0), 0), 0);
+        assert_eq!(scoring.lock(perfect_clear(4), 0), 1200 + 3200);
+    }
+
+    #[test]
+    fn t_spins_are_not_scored_by_nes() {
+        let mut scoring = scoring(ScoringName::Nes);
+        assert_eq!(scoring.lock(t_spin(0, TSpin::Full), 0), 0);
+        assert_eq!(scoring.lock(t_spin(2, TSpin::Full), 0), 100);
+    }
+
+    #[test]
+    fn labels() {
+        assert!(clear(0).labels().is_empty());
+        assert_eq!(clear(4).labels(), vec!["TETRIS"]);
+        assert_eq!(t_spin(0, TSpin::Full).labels(), vec!["T-SPIN"]);
+        assert_eq!(
+            t_spin(2, TSpin::Mini).labels(),
+            vec!["T-SPIN MINI", "DOUBLE"]
+        );
+        assert_eq!(perfect_clear(1).labels(), vec!["SINGLE", "ALL CLEAR"]);
+    }
+
+    #[test]
+    fn nes_formula() {
+        let mut scoring = scoring(ScoringName::Nes);
+        assert_eq!(scoring.lock(clear(1), 0), 40);
+        assert_eq!(scoring.lock(clear(2), 1), 200);
+        assert_eq!(scoring.lock(clear(3), 2), 900);
+        assert_eq!(scoring.lock(clear(4), 9), 12000);
+        assert_eq!(scoring.lock(clear(4), 9), 12000);
+        assert_eq!(scoring.hard_drop(10), 0);
+    }
+}
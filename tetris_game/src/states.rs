@@ -1,9 +1,36 @@
 use crate::conf::TetrisSettings;
+use crate::highscores::HighScores;
+use crate::mode::{GameMode, GameOptions};
+use crate::random::GameRng;
+use crate::replay::Replay;
 use crate::{GamePad, GameUI};
 
+/// The states to create, with the data they are created from if any.
 pub enum StateName {
-    Intro,
-    Ongoing,
+    Menu,
+    Ongoing(GameOptions),
+    Pause,
+    /// The results of a game, with its rank in the high scores if it made it.
+    Results(Box<GameResults>, Option<usize>),
+    Replay(Box<Replay>),
+    HighScores(GameMode),
+    Settings,
+}
+
+/// What to do with the state stack at the end of a game loop.
+pub enum Transition {
+    /// Put a new state on top of the current one, which is kept as is
+    /// underneath, e.g. for an overlay like the pause.
+    Push(StateName),
+    /// Remove the current state, going back to the one underneath.
+    Pop,
+    /// Replace the current state with a new one.
+    Replace(StateName),
+    /// End the game: record its results into the high scores, then show them
+    /// in place of the current state.
+    GameOver(Box<GameResults>),
+    /// Quit the game altogether.
+    Quit,
 }
 
 pub trait State {
@@ -15,18 +42,45 @@ pub trait State {
 
     fn draw(&self, ui: &mut dyn GameUI);
 
-    fn end_loop(&self) -> Option<StateName>;
+    /// Ask the state to pause, e.g. when the front end stalls.
+    /// Ignored by default.
+    fn pause(&mut self) {}
+
+    fn end_loop(&mut self) -> Option<Transition>;
 }
 
-mod intro;
+mod highscores;
+mod menu;
 mod ongoing;
+mod pause;
+mod replay;
+mod results;
+mod settings;
 
-use intro::Intro;
+use highscores::HighScoresView;
+use menu::Menu;
 use ongoing::Ongoing;
+use pause::Pause;
+use replay::ReplayViewer;
+use results::{GameResults, Results};
+use settings::SettingsView;
 
-pub fn create_state<'a>(name: StateName, settings: &'a TetrisSettings) -> Box<dyn State + 'a> {
+/// Create a state.
+pub fn create_state<'a>(
+    name: StateName,
+    settings: &'a TetrisSettings,
+    rng: &mut GameRng,
+    high_scores: &HighScores,
+) -> Box<dyn State + 'a> {
     match name {
-        StateName::Intro => Box::new(Intro::new(settings)),
-        StateName::Ongoing => Box::new(Ongoing::new(settings)),
+        StateName::Menu => Box::new(Menu::new(settings, rng)),
+        StateName::Ongoing(options) => Box::new(Ongoing::new(settings, options, rng)),
+        StateName::Pause => Box::new(Pause::new(settings)),
+        StateName::Results(results, rank) => Box::new(Results::new(settings, results, rank)),
+        StateName::Replay(replay) => Box::new(ReplayViewer::new(settings, replay)),
+        StateName::HighScores(mode) => {
+            Box::new(HighScoresView::new(settings, high_scores.clone(), mode))
+        }
+        StateName::Settings => Box::new(SettingsView::new(settings)),
     }
 }
@@ -0,0 +1,70 @@
+use crate::conf::TetrisSettings;
+use crate::highscores::HighScores;
+use crate::mode::GameMode;
+use crate::{Button, GamePad, GameUI, Position};
+
+use super::results::format_centis;
+use super::{State, Transition};
+
+/// The tables of the best games, shown from the main menu one mode at a time.
+pub struct HighScoresView {
+    high_scores: HighScores,
+    mode: GameMode,
+    is_closed: bool,
+}
+
+impl HighScoresView {
+    pub fn new(_settings: &TetrisSettings, high_scores: HighScores, mode: GameMode) -> Self {
+        Self {
+            high_scores,
+            mode,
+            is_closed: false,
+        }
+    }
+}
+
+impl State for HighScoresView {
+    fn start_loop(&mut self) {}
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        if pad.is_pressed(Button::Left) {
+            self.mode = self.mode.cycle(-1);
+        }
+        if pad.is_pressed(Button::Right) {
+            self.mode = self.mode.cycle(1);
+        }
+        if pad.is_pressed(Button::Start) || pad.is_pressed(Button::A) || pad.is_pressed(Button::B) {
+            self.is_closed = true;
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        ui.draw_text(
+            Position::new(1, 2),
+            &format!("High Scores - < {} >", self.mode.name()),
+        );
+        let entries = self.high_scores.entries(self.mode);
+        if entries.is_empty() {
+            ui.draw_text(Position::new(1, 4), "No games played yet");
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let y = 4 + i as i16;
+            ui.draw_text(Position::new(1, y), &format!("{}.", i + 1));
+            ui.draw_text(Position::new(3, y), &entry.score.to_string());
+            ui.draw_text(Position::new(9, y), &format!("Lv {}", entry.level));
+            ui.draw_text(Position::new(13, y), &format!("{} lines", entry.n_lines));
+            ui.draw_text(Position::new(18, y), &format_centis(entry.centis));
+        }
+        ui.draw_text(Position::new(1, 16), "Press Start to go back");
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        if self.is_closed {
+            Some(Transition::Pop)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use rand::Rng;
+
+use crate::conf::TetrisSettings;
+use crate::mode::{GameOptions, MAX_START_LEVEL};
+use crate::random::{fork_rng, GameRng};
+use crate::{Button, Color, GamePad, GameUI, Position};
+
+use super::{State, StateName, Transition};
+
+/// The items of the main menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    NewGame,
+    Mode,
+    StartLevel,
+    HighScores,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 6] = [
+        MenuItem::NewGame,
+        MenuItem::Mode,
+        MenuItem::StartLevel,
+        MenuItem::HighScores,
+        MenuItem::Settings,
+        MenuItem::Quit,
+    ];
+}
+
+/// The main menu, below the animated logo. Up and down select an item, left
+/// and right change the selected option, and Start (or A) chooses the item.
+pub struct Menu {
+    rng: GameRng,
+    loop_count: i32,
+    bricks: Vec<Position>,
+    color: Color,
+    options: GameOptions,
+    selected: usize,
+    is_chosen: bool,
+}
+
+impl Menu {
+    pub fn new(settings: &TetrisSettings, rng: &mut GameRng) -> Self {
+        let tetris_positions = vec![
+            // T
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (2, 2),
+            (2, 3),
+            (2, 4),
+            (2, 5),
+            // E
+            (5, 1),
+            (6, 1),
+            (5, 2),
+            (5, 3),
+            (5, 4),
+            (5, 5),
+            (6, 5),
+            (6, 3),
+            // t
+            (9, 2),
+            (9, 3),
+            (9, 4),
+            (9, 5),
+            (10, 5),
+            (8, 3),
+            (10, 3),
+            // r
+            (12, 3),
+            (12, 4),
+            (12, 5),
+            (13, 3),
+            // i
+            (15, 1),
+            (15, 3),
+            (15, 4),
+            (15, 5),
+            // s
+            (18, 2),
+            (17, 2),
+            (17, 3),
+            (18, 4),
+            (18, 5),
+            (17, 5),
+        ];
+        let bricks: Vec<Position> = tetris_positions
+            .into_iter()
+            .map(|(x, y)| Position::new(x, y))
+            .collect();
+        Self {
+            rng: fork_rng(rng),
+            loop_count: 0,
+            bricks,
+            color: Color::Gray,
+            options: GameOptions::new(settings),
+            selected: 0,
+            is_chosen: false,
+        }
+    }
+
+    fn selected_item(&self) -> MenuItem {
+        MenuItem::ALL[self.selected]
+    }
+
+    /// Change the option of the selected item, if any, by `step` values.
+    fn change_option(&mut self, step: isize) {
+        match self.selected_item() {
+            MenuItem::Mode => {
+                self.options.mode = self.options.mode.cycle(step);
+            }
+            MenuItem::StartLevel => {
+                let n_levels = isize::from(MAX_START_LEVEL) + 1;
+                let level = (isize::from(self.options.start_level) + step).rem_euclid(n_levels);
+                self.options.start_level = level as u8;
+            }
+            _ => {}
+        }
+    }
+
+    fn item_text(&self, item: MenuItem) -> String {
+        match item {
+            MenuItem::NewGame => String::from("New Game"),
+            MenuItem::Mode => format!("Mode: < {} >", self.options.mode.name()),
+            MenuItem::StartLevel => format!("Level: < {} >", self.options.start_level),
+            MenuItem::HighScores => String::from("High Scores"),
+            MenuItem::Settings => String::from("View settings"),
+            MenuItem::Quit => String::from("Quit"),
+        }
+    }
+}
+
+impl State for Menu {
+    fn start_loop(&mut self) {
+        self.loop_count += 1;
+    }
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        let n_items = MenuItem::ALL.len();
+        if pad.is_pressed(Button::Up) {
+            self.selected = (self.selected + n_items - 1) % n_items;
+        }
+        if pad.is_pressed(Button::Down) {
+            self.selected = (self.selected + 1) % n_items;
+        }
+        if pad.is_pressed(Button::Left) {
+            self.change_option(-1);
+        }
+        if pad.is_pressed(Button::Right) {
+            self.change_option(1);
+        }
+        if pad.is_pressed(Button::Start) || pad.is_pressed(Button::A) {
+            self.is_chosen = true;
+        }
+    }
+
+    fn update(&mut self) {
+        if self.loop_count % 20 == 0 {
+            self.color = pick_random_color(&mut self.rng);
+        }
+    }
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        for pos in &self.bricks {
+            ui.draw_brick(*pos, self.color);
+        }
+        for (i, &item) in MenuItem::ALL.iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            ui.draw_text(
+                Position::new(5, 9 + 2 * i as i16),
+                &format!("{} {}", marker, self.item_text(item)),
+            );
+        }
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        if !self.is_chosen {
+            return None;
+        }
+        self.is_chosen = false;
+        match self.selected_item() {
+            MenuItem::NewGame => {
+                log::info!("Starting game: {:?}", self.options);
+                Some(Transition::Replace(StateName::Ongoing(self.options)))
+            }
+            // Choosing an option is the same as changing it.
+            MenuItem::Mode | MenuItem::StartLevel => {
+                self.change_option(1);
+                None
+            }
+            MenuItem::HighScores => {
+                Some(Transition::Push(StateName::HighScores(self.options.mode)))
+            }
+            MenuItem::Settings => Some(Transition::Push(StateName::Settings)),
+            MenuItem::Quit => {
+                log::info!("Quitting");
+                Some(Transition::Quit)
+            }
+        }
+    }
+}
+
+fn pick_random_color(rng: &mut GameRng) -> Color {
+    let n: u8 = rng.gen();
+    match n % 7 {
+        0 => Color::Teal,
+        1 => Color::Yellow,
+        2 => Color::Purple,
+        3 => Color::Blue,
+        4 => Color::Orange,
+        5 => Color::Green,
+        6 => Color::Red,
+        _ => panic!("Impossible!"),
+    }
+}
@@ -1,79 +1,275 @@
+use std::collections::VecDeque;
 use std::mem;
 
-use rand::random;
-
+use crate::autoshift::{AutoShift, Shift};
 use crate::conf::TetrisSettings;
+use crate::gravity::Gravity;
+use crate::lock::LockDelay;
+use crate::mode::{GameMode, GameOptions};
 use crate::playfield::PlayField;
+use crate::progression::{create_level_progression, LevelProgression};
+use crate::random::{fork_rng, GameRng};
+use crate::randomizer::{create_randomizer, Randomizer};
+use crate::replay::Replay;
+use crate::rotation::{get_rotation_system, RotationSystem};
+use crate::scoring::{create_scoring, Clear, Scoring};
+use crate::statistics::Statistics;
 use crate::tetromino::{GameWorld, Shape, Tetromino};
+use crate::tspin::detect_t_spin;
 use crate::{Button, Color, GamePad, GameUI, Position};
 
-use super::{State, StateName};
+use super::results::{format_time, GameResults};
+use super::{State, StateName, Transition};
+
+/// The phases the game goes through for each tetromino, from spawning to
+/// locking it into the play field.
+enum Phase {
+    /// The active tetromino is falling and can be controlled by the player.
+    Falling,
+    /// The completed rows are being cleared, and will be destroyed when the
+    /// line clear delay expires.
+    LineClear { rows: Vec<i16>, elapsed: u16 },
+    /// Waiting for the entry delay (ARE) to expire to spawn the next tetromino.
+    Entry { elapsed: u16 },
+}
+
+/// Number of game loops the clear labels (e.g. "T-SPIN DOUBLE") are shown for.
+const CLEAR_LABELS_LOOPS: u16 = 60;
 
 pub struct Ongoing<'a> {
     settings: &'a TetrisSettings,
+    options: GameOptions,
+    rng: GameRng,
+    randomizer: Box<dyn Randomizer>,
+    scoring: Box<dyn Scoring>,
+    level_progression: Box<dyn LevelProgression>,
+    rotation_system: &'static dyn RotationSystem,
 
     loop_count: i32,
 
-    next_tetromino: Option<Tetromino>,
+    next_tetrominoes: VecDeque<Tetromino>,
     active_tetromino: Option<Tetromino>,
+    held_tetromino: Option<Tetromino>,
+    is_hold_used: bool,
+    lock_delay: LockDelay,
+    auto_shift: AutoShift,
+    is_new_tetromino: bool,
+    is_soft_dropping: bool,
+    gravity: Gravity,
+    phase: Phase,
     play_field: PlayField,
     score: u32,
+    statistics: Statistics,
+    replay: Replay,
+    clear_labels: Vec<&'static str>,
+    loops_since_clear: u16,
     cheat_codes: String,
     is_game_over: bool,
-    is_restarted: bool,
+    /// Whether the game ended by reaching the goal of its mode.
+    is_completed: bool,
+    is_paused: bool,
+    is_finished: bool,
     is_debug_enabled: bool,
 }
 
 impl<'a> Ongoing<'a> {
-    pub fn new(settings: &'a TetrisSettings) -> Self {
+    pub fn new(settings: &'a TetrisSettings, options: GameOptions, rng: &mut GameRng) -> Self {
+        Self::with_rng(settings, options, fork_rng(rng))
+    }
+
+    /// Create a game driven by the given random number generator, e.g. to
+    /// play a replay again.
+    pub fn with_rng(settings: &'a TetrisSettings, options: GameOptions, rng: GameRng) -> Self {
         Self {
             settings,
+            options,
+            replay: Replay::new(options, rng.clone()),
+            rng,
+            randomizer: create_randomizer(settings.randomizer),
+            scoring: create_scoring(settings),
+            level_progression: create_level_progression(settings, options.start_level),
+            rotation_system: get_rotation_system(settings.rotation_system),
 
             loop_count: 0,
-            next_tetromino: None,
+            next_tetrominoes: VecDeque::new(),
             active_tetromino: None,
-            play_field: PlayField::new(settings.play_field_width, settings.play_field_height),
+            held_tetromino: None,
+            is_hold_used: false,
+            lock_delay: LockDelay::new(settings),
+            auto_shift: AutoShift::new(settings),
+            is_new_tetromino: false,
+            is_soft_dropping: false,
+            gravity: Gravity::new(settings),
+            phase: Phase::Entry { elapsed: 0 },
+            play_field: PlayField::new(
+                settings.play_field_width,
+                settings.play_field_height,
+                settings.buffer_height,
+            ),
             score: 0,
+            statistics: Statistics::default(),
+            clear_labels: Vec::new(),
+            loops_since_clear: 0,
             cheat_codes: String::new(),
             is_game_over: false,
-            is_restarted: false,
+            is_completed: false,
+            is_paused: false,
+            is_finished: false,
             is_debug_enabled: false,
         }
     }
 
+    /// Where the tetrominoes spawn: centered, and in the 2 hidden rows right
+    /// above the visible play field if the buffer allows it.
     fn top_center_pos(&self) -> Position {
-        Position::new(self.play_field.width() / 2 - 2, 0)
+        let y = -self.play_field.buffer_height().min(2);
+        Position::new(self.play_field.width() / 2 - 2, y)
     }
 
     fn level(&self) -> u8 {
-        let level = self.score / self.settings.score_per_level;
-        if level <= u8::MAX as u32 {
-            level as u8
-        } else {
-            u8::MAX
+        self.level_progression.level()
+    }
+
+    /// The number of tetrominoes shown in the next queue.
+    fn next_queue_length(&self) -> usize {
+        self.settings.next_queue_length.clamp(1, 6) as usize
+    }
+
+    fn take_next_tetromino(&mut self) -> Tetromino {
+        // Keep the next queue filled with new random tetrominoes, then take the first one out.
+        while self.next_tetrominoes.len() <= self.next_queue_length() {
+            let shape = self.randomizer.next_shape(&mut self.rng);
+            let tetromino = Tetromino::new(shape, Position::new(0, 0), self.rotation_system);
+            self.next_tetrominoes.push_back(tetromino);
         }
+        let shape = match self.next_tetrominoes.pop_front() {
+            Some(tetromino) => tetromino.shape(),
+            None => panic!("Impossible!"),
+        };
+        Tetromino::new(shape, self.top_center_pos(), self.rotation_system)
     }
 
-    /// The current fall pace, computed from `level`. Fall pace is the number
-    /// of game loops for the tetromino to fall by one unit. The smaller the
-    /// fall pace is, the faster the game speed is.
-    fn fall_pace(&self) -> u8 {
-        let level = self.level();
-        let fall_pace = if self.settings.fall_pace_slowest > level {
-            self.settings.fall_pace_slowest - level
+    /// Make the given tetromino the active one if there is free space
+    /// for it, or end the game otherwise (block out).
+    fn spawn(&mut self, mut tetromino: Tetromino) {
+        if self.play_field.is_free(tetromino.bricks()) {
+            // Spawned in the hidden rows, it drops by one row right away if it can.
+            if tetromino.bricks().iter().any(|brick| brick.xy().1 < 0) {
+                tetromino.fall_down(&self.play_field);
+            }
+            self.lock_delay.restart();
+            self.active_tetromino = Some(tetromino);
+            self.is_new_tetromino = true;
+            self.gravity.reset();
+            self.phase = Phase::Falling;
         } else {
-            0
-        };
-        fall_pace.max(self.settings.fall_pace_fastest)
+            log::info!("No free space for new tetromino: Game is over!");
+            self.top_out();
+        }
     }
 
-    fn take_next_tetromino(&mut self) -> Tetromino {
-        // Swap in a new random tetromino into `next_tetromino`, getting its current value out.
-        let mut next_tetromino = Some(Tetromino::new(Shape::pick(random()), Position::new(0, 0)));
-        mem::swap(&mut self.next_tetromino, &mut next_tetromino);
-        match next_tetromino {
-            Some(tetromino) => Tetromino::new(tetromino.shape(), self.top_center_pos()),
-            None => Tetromino::new(Shape::pick(random()), self.top_center_pos()),
+    fn top_out(&mut self) {
+        self.play_field.fade_to_gray();
+        self.is_game_over = true;
+    }
+
+    /// End the game as the goal of its mode is reached, e.g. the lines of a
+    /// Sprint or the time of an Ultra.
+    fn complete(&mut self) {
+        log::info!("{} completed: Game is over!", self.options.mode.name());
+        self.play_field.destroy_completed_rows();
+        self.phase = Phase::Entry { elapsed: 0 };
+        self.is_game_over = true;
+        self.is_completed = true;
+    }
+
+    /// Number of game loops an Ultra game lasts.
+    fn ultra_loops(&self) -> i32 {
+        self.settings.ultra_seconds as i32 * i32::from(self.settings.loops_per_second)
+    }
+
+    /// Swap the active tetromino with the held one (or the next one if none is
+    /// held yet). This can be done only once until the active tetromino is locked.
+    fn hold(&mut self) {
+        if !self.settings.enable_hold || self.is_hold_used {
+            return;
+        }
+        if let Some(active_tetromino) = self.active_tetromino.take() {
+            let shape = active_tetromino.shape();
+            let mut held_tetromino = Some(Tetromino::new(
+                shape,
+                Position::new(0, 0),
+                self.rotation_system,
+            ));
+            mem::swap(&mut self.held_tetromino, &mut held_tetromino);
+            let tetromino = match held_tetromino {
+                Some(tetromino) => Tetromino::new(
+                    tetromino.shape(),
+                    self.top_center_pos(),
+                    self.rotation_system,
+                ),
+                None => self.take_next_tetromino(),
+            };
+            self.is_hold_used = true;
+            self.spawn(tetromino);
+        }
+    }
+
+    /// Lock the active tetromino into the play field, then start clearing the
+    /// completed rows if any, or wait for the next tetromino to spawn. The game
+    /// ends if the tetromino is locked out and does not complete any row.
+    fn lock_active_tetromino(&mut self) {
+        if let Some(tetromino) = self.active_tetromino.take() {
+            let t_spin = detect_t_spin(&tetromino, &self.play_field);
+            self.play_field
+                .fill_space(tetromino.bricks(), tetromino.color());
+            self.is_hold_used = false;
+            let rows = self.play_field.completed_rows();
+            let clear = Clear {
+                n_rows: rows.len() as u32,
+                t_spin,
+                is_perfect_clear: self.play_field.is_perfect_clear(),
+            };
+            self.score += self.scoring.lock(clear, self.level());
+            self.level_progression.on_lock(clear, self.score);
+            self.statistics.record(clear);
+            let clear_labels = clear.labels();
+            if !clear_labels.is_empty() {
+                self.clear_labels = clear_labels;
+                self.loops_since_clear = 0;
+            }
+            if self.options.mode == GameMode::Sprint
+                && self.statistics.n_lines >= self.settings.sprint_lines
+            {
+                self.complete();
+                return;
+            }
+            if rows.is_empty() && self.settings.top_out_rule.is_locked_out(tetromino.bricks()) {
+                log::info!("Tetromino locked out: Game is over!");
+                self.top_out();
+                return;
+            }
+            self.phase = if rows.is_empty() {
+                Phase::Entry { elapsed: 0 }
+            } else {
+                Phase::LineClear { rows, elapsed: 0 }
+            };
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.is_game_over
+    }
+
+    pub fn results(&self) -> GameResults {
+        GameResults {
+            options: self.options,
+            score: self.score,
+            level: self.level(),
+            n_loops: self.loop_count.max(0) as u32,
+            statistics: self.statistics.clone(),
+            replay: self.replay.clone(),
+            is_completed: self.is_completed,
         }
     }
 
@@ -86,7 +282,11 @@ impl<'a> Ongoing<'a> {
 
         match cheat_codes {
             "solongmarianne" => {
-                self.next_tetromino = Some(Tetromino::new(Shape::I, Position::new(0, 0)));
+                let tetromino = Tetromino::new(Shape::I, Position::new(0, 0), self.rotation_system);
+                match self.next_tetrominoes.front_mut() {
+                    Some(next_tetromino) => *next_tetromino = tetromino,
+                    None => self.next_tetrominoes.push_back(tetromino),
+                }
             }
             "paintitblack" => {
                 self.play_field.clear();
@@ -118,42 +318,103 @@ impl<'a> State for Ongoing<'a> {
             return;
         }
         self.loop_count += 1;
-        if self.active_tetromino.is_none() {
-            let tetromino = self.take_next_tetromino();
-            if self.play_field.is_free(tetromino.bricks()) {
-                self.active_tetromino = Some(tetromino);
+        if self.options.mode == GameMode::Ultra && self.loop_count >= self.ultra_loops() {
+            self.complete();
+            return;
+        }
+        self.loops_since_clear = self.loops_since_clear.saturating_add(1);
+        if let Phase::LineClear { elapsed, .. } = &mut self.phase {
+            if *elapsed >= self.settings.line_clear_delay {
+                self.play_field.destroy_completed_rows();
+                self.phase = Phase::Entry { elapsed: 0 };
+            } else {
+                *elapsed += 1;
+            }
+        }
+        if let Phase::Entry { elapsed } = &mut self.phase {
+            if *elapsed >= self.settings.entry_delay {
+                let tetromino = self.take_next_tetromino();
+                self.spawn(tetromino);
             } else {
-                log::info!("No free space for new tetromino: Game is over!");
-                self.play_field.fade_to_gray();
-                self.is_game_over = true;
+                *elapsed += 1;
             }
         }
     }
 
     fn process_input(&mut self, pad: &dyn GamePad) {
+        if !self.is_game_over {
+            self.replay.record(pad);
+        }
+
         // Toggle debug mode: Usable no matter if game is over.
         if pad.is_pressed(Button::Select) {
             self.is_debug_enabled = !self.is_debug_enabled;
         }
 
-        // If game is over, the only thing user can do is to restart the game.
+        // If game is over, the only thing user can do is to go to the results.
         if self.is_game_over {
             if pad.is_pressed(Button::Start) {
-                self.is_restarted = true;
+                self.is_finished = true;
             }
             return;
         }
 
+        if pad.is_pressed(Button::Pause) {
+            self.pause();
+            return;
+        }
+
+        // Soft drop applies as long as the button is held, in `update`.
+        self.is_soft_dropping = pad.held_loops(Button::Down) > 0;
+
         // Control the active tetromino.
+        let mut is_hard_dropped = false;
         if let Some(tetromino) = self.active_tetromino.as_mut() {
-            tetromino.move_towards(pad.direction(), &self.play_field);
-            if pad.is_pressed(Button::A) {
-                tetromino.rotate_right(&self.play_field);
+            // Shift horizontally: if both directions are held, the latest one wins.
+            let held_left = pad.held_loops(Button::Left);
+            let held_right = pad.held_loops(Button::Right);
+            let (dx, held_loops) = match (held_left, held_right) {
+                (0, 0) => (0, 0),
+                (_, 0) => (-1, held_left),
+                (0, _) => (1, held_right),
+                _ if held_left < held_right => (-1, held_left),
+                _ => (1, held_right),
+            };
+            let n_cells = match self.auto_shift.shift(held_loops, self.is_new_tetromino) {
+                Shift::None => 0,
+                Shift::One => 1,
+                Shift::ToWall => self.play_field.width(),
+            };
+            for _ in 0..n_cells {
+                if !tetromino.move_towards((dx, 0), &self.play_field) {
+                    break;
+                }
+                self.lock_delay.on_moved();
+            }
+
+            if pad.is_pressed(Button::A) && tetromino.rotate_right(&self.play_field) {
+                self.lock_delay.on_moved();
+            }
+            if pad.is_pressed(Button::X) && tetromino.rotate_left(&self.play_field) {
+                self.lock_delay.on_moved();
+            }
+            if pad.is_pressed(Button::Y) && tetromino.rotate_180(&self.play_field) {
+                self.lock_delay.on_moved();
             }
             if pad.is_pressed(Button::B) {
-                tetromino.fall_to_bottom(&self.play_field);
+                let n_rows = tetromino.fall_to_bottom(&self.play_field);
+                self.score += self.scoring.hard_drop(n_rows);
+                is_hard_dropped = true;
             }
         }
+        self.is_new_tetromino = false;
+        if is_hard_dropped {
+            // A hard-dropped tetromino is locked immediately, without lock delay.
+            self.lock_active_tetromino();
+        }
+        if pad.is_pressed(Button::L) {
+            self.hold();
+        }
 
         // Cheating...
         if let Some(cheat_code) = pad.cheat_code() {
@@ -172,26 +433,45 @@ impl<'a> State for Ongoing<'a> {
         if self.is_game_over {
             return;
         }
-        let fall_pace = self.fall_pace();
-        if self.loop_count % (fall_pace as i32) == 0 {
-            if let Some(tetromino) = self.active_tetromino.as_mut() {
-                let has_fallen_down = tetromino.fall_down(&self.play_field);
-                if !has_fallen_down {
-                    // The tetromino has reached the bottom.
-                    self.play_field
-                        .fill_space(tetromino.bricks(), tetromino.color());
-                    self.active_tetromino = None;
-                    let n_rows_destroyed = self.play_field.destroy_completed_rows();
-                    self.score += if n_rows_destroyed > 0 {
-                        let max_index = self.settings.scores_for_rows_destroyed.len() - 1;
-                        let index = max_index.min((n_rows_destroyed - 1) as usize);
-                        self.settings.scores_for_rows_destroyed[index]
-                    } else {
-                        0
-                    };
+        let level = self.level();
+        let soft_drop_factor = self.settings.soft_drop_factor;
+        let mut n_rows_soft_dropped = 0;
+        let mut is_to_lock = false;
+        if let Some(tetromino) = self.active_tetromino.as_mut() {
+            if self.is_soft_dropping && soft_drop_factor == 0 {
+                // Instant soft drop (sonic drop): fall to the bottom, but without locking.
+                while tetromino.fall_down(&self.play_field) {
+                    self.lock_delay.on_fallen(tetromino.bottom_row());
+                    n_rows_soft_dropped += 1;
                 }
+            } else {
+                let factor = if self.is_soft_dropping {
+                    f32::from(soft_drop_factor)
+                } else {
+                    1.0
+                };
+                let n_cells = self.gravity.tick(level, factor);
+                for _ in 0..n_cells {
+                    if !tetromino.fall_down(&self.play_field) {
+                        // Do not accumulate gravity while on the ground.
+                        self.gravity.reset();
+                        break;
+                    }
+                    self.lock_delay.on_fallen(tetromino.bottom_row());
+                    if self.is_soft_dropping {
+                        n_rows_soft_dropped += 1;
+                    }
+                }
+            }
+            if tetromino.is_on_ground(&self.play_field) {
+                // The tetromino has reached the bottom: lock it when the lock delay expires.
+                is_to_lock = self.lock_delay.tick();
             }
         }
+        self.score += self.scoring.soft_drop(n_rows_soft_dropped);
+        if is_to_lock {
+            self.lock_active_tetromino();
+        }
     }
 
     fn draw(&self, ui: &mut dyn GameUI) {
@@ -211,60 +491,428 @@ impl<'a> State for Ongoing<'a> {
             ui.draw_brick(Position::new(x, self.play_field.height()), wall_color);
         }
 
-        // Draw the inactive bricks in the play field and the active tetromino.
+        // Draw the inactive bricks in the play field and the active tetromino,
+        // except those in the hidden rows.
         // Note: We move the bricks to the right by 1 unit to leave room for the left wall.
         let right_by_1 = (1, 0);
+        let is_visible = |brick: &&Position| brick.xy().1 >= 0;
         for (position, color) in self.play_field.space() {
-            ui.draw_brick(position.updated(right_by_1), *color);
+            if !is_visible(&position) {
+                continue;
+            }
+            match &self.phase {
+                Phase::LineClear { rows, elapsed } if rows.contains(&position.xy().1) => {
+                    let delay = self.settings.line_clear_delay.max(1);
+                    let progress = f32::from(*elapsed) / f32::from(delay);
+                    ui.draw_clearing_brick(position.updated(right_by_1), *color, progress);
+                }
+                _ => ui.draw_brick(position.updated(right_by_1), *color),
+            }
         }
         if let Some(tetromino) = self.active_tetromino.as_ref() {
             let color = tetromino.color();
-            for brick in tetromino.bricks() {
+            if self.settings.show_ghost {
+                let mut ghost = tetromino.clone();
+                ghost.fall_to_bottom(&self.play_field);
+                for brick in ghost.bricks().iter().filter(is_visible) {
+                    ui.draw_ghost_brick(brick.updated(right_by_1), color);
+                }
+            }
+            for brick in tetromino.bricks().iter().filter(is_visible) {
                 ui.draw_brick(brick.updated(right_by_1), color);
             }
         }
 
-        // Texts are shown on the right panel, so leave space for the play field
-        // + 2 units for the wall + 2 units for left margin.
+        // The right panel is split into 2 columns: the left one shows the held
+        // tetromino and the texts, and the right one shows the next queue.
+        // Leave space for the play field + 2 units for the wall + 2 units for left margin.
         let text_x = self.play_field.width() + 4;
+        let next_x = text_x + 5;
 
-        ui.draw_text(Position::new(text_x, 1), &format!("Score: {}", self.score));
-        ui.draw_text(
-            Position::new(text_x, 2),
-            &format!("Level: {}", self.level()),
-        );
-        ui.draw_text(Position::new(text_x, 3), &self.cheat_codes);
-        if let Some(next_tetromino) = self.next_tetromino.as_ref() {
-            ui.draw_text(Position::new(text_x, 4), "Next:");
-            let aligned_with_text = (text_x, 5);
+        ui.draw_text(Position::new(next_x, 1), "Next:");
+        for (i, next_tetromino) in self.next_tetrominoes.iter().enumerate() {
+            // Each tetromino in the queue takes up to 2 rows, plus 1 row as spacing.
+            let aligned_with_text = (next_x, 2 + 3 * i as i16);
             let color = next_tetromino.color();
             for brick in next_tetromino.bricks() {
                 ui.draw_brick(brick.updated(aligned_with_text), color);
             }
         }
-        if self.is_game_over {
-            ui.draw_text(Position::new(text_x, 9), "Game Over!");
+        if self.settings.enable_hold {
+            // The held tetromino is grayed out while hold cannot be used.
+            ui.draw_text(Position::new(text_x, 1), "Hold:");
+            if let Some(held_tetromino) = self.held_tetromino.as_ref() {
+                let aligned_with_text = (text_x, 2);
+                let color = if self.is_hold_used {
+                    Color::Gray
+                } else {
+                    held_tetromino.color()
+                };
+                for brick in held_tetromino.bricks() {
+                    ui.draw_brick(brick.updated(aligned_with_text), color);
+                }
+            }
+        }
+
+        // The texts must fit in the 5 columns left of the next queue.
+        // Ultra counts down the time left, the other modes the time elapsed.
+        let n_loops = match self.options.mode {
+            GameMode::Ultra => self.ultra_loops() - self.loop_count,
+            GameMode::Marathon | GameMode::Sprint => self.loop_count,
+        };
+        ui.draw_text(
+            Position::new(text_x, 5),
+            &format_time(n_loops.max(0) as u32, self.settings.loops_per_second),
+        );
+        ui.draw_text(Position::new(text_x, 6), &format!("Score: {}", self.score));
+        ui.draw_text(
+            Position::new(text_x, 7),
+            &format!("Level: {}", self.level()),
+        );
+        let lines = match self.options.mode {
+            GameMode::Sprint => {
+                format!(
+                    "Lines: {}/{}",
+                    self.statistics.n_lines, self.settings.sprint_lines
+                )
+            }
+            GameMode::Marathon | GameMode::Ultra => format!("Lines: {}", self.statistics.n_lines),
+        };
+        ui.draw_text(Position::new(text_x, 8), &lines);
+        ui.draw_text(Position::new(text_x, 9), &self.cheat_codes);
+        if self.loops_since_clear < CLEAR_LABELS_LOOPS {
+            for (i, label) in self.clear_labels.iter().enumerate() {
+                ui.draw_text(Position::new(text_x, 10 + i as i16), label);
+            }
+        }
+        if self.is_completed {
+            ui.draw_text(Position::new(text_x, 13), "Complete!");
+        } else if self.is_game_over {
+            ui.draw_text(Position::new(text_x, 13), "Game Over!");
         }
 
         if self.is_debug_enabled {
-            ui.draw_text(Position::new(text_x, 11), "---- DEBUG ----");
+            ui.draw_text(Position::new(text_x, 15), "---- DEBUG ----");
             ui.draw_text(
-                Position::new(text_x, 12),
+                Position::new(text_x, 16),
                 &format!("Loop count: {}", self.loop_count),
             );
             ui.draw_text(
-                Position::new(text_x, 13),
-                &format!("Fall pace: {}", self.fall_pace()),
+                Position::new(text_x, 17),
+                &format!("Gravity: {:.3}G", self.gravity.at_level(self.level())),
+            );
+            ui.draw_text(
+                Position::new(text_x, 18),
+                &format!("Pieces: {}", self.statistics.n_pieces),
             );
+            ui.draw_text(
+                Position::new(text_x, 19),
+                &format!("Perfect clears: {}", self.statistics.n_perfect_clears),
+            );
+        }
+    }
+
+    fn pause(&mut self) {
+        if !self.is_game_over {
+            self.is_paused = true;
         }
     }
 
-    fn end_loop(&self) -> Option<StateName> {
-        if self.is_game_over && self.is_restarted {
-            log::info!("Transitioning state: Ongoing to Intro");
-            Some(StateName::Intro)
+    fn end_loop(&mut self) -> Option<Transition> {
+        if self.is_paused {
+            log::info!("Transitioning state: Ongoing to Pause");
+            self.is_paused = false;
+            Some(Transition::Push(StateName::Pause))
+        } else if self.is_game_over && self.is_finished {
+            log::info!("Transitioning state: Ongoing to Results");
+            let results = self.results();
+            Some(Transition::GameOver(Box::new(results)))
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TestGamePad;
+    use crate::progression::LevelProgressionName;
+    use crate::random::create_rng;
+    use crate::states::pause::Pause;
+
+    fn create_game(settings: &TetrisSettings) -> Ongoing<'_> {
+        Ongoing::with_rng(settings, GameOptions::new(settings), create_rng(Some(0)))
+    }
+
+    fn run_loop(game: &mut Ongoing, pad: &TestGamePad) {
+        game.start_loop();
+        game.process_input(pad);
+        game.update();
+    }
+
+    /// Start game loops until a tetromino spawns, returning the number of loops.
+    fn start_loops_until_spawn(game: &mut Ongoing) -> u16 {
+        for n_loops in 1..=100 {
+            game.start_loop();
+            if game.active_tetromino.is_some() {
+                return n_loops;
+            }
+        }
+        panic!("No tetromino spawned");
+    }
+
+    fn active_shape(game: &Ongoing) -> Shape {
+        game.active_tetromino.as_ref().unwrap().shape()
+    }
+
+    fn held_shape(game: &Ongoing) -> Option<Shape> {
+        game.held_tetromino.as_ref().map(Tetromino::shape)
+    }
+
+    #[test]
+    fn hold_once_per_tetromino() {
+        let settings = TetrisSettings::default();
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        let first = active_shape(&game);
+
+        // Nothing is held yet: the next tetromino comes in.
+        run_loop(&mut game, &TestGamePad::pressed(Button::L));
+        let second = active_shape(&game);
+        assert_ne!(second, first);
+        assert_eq!(held_shape(&game), Some(first));
+
+        // Holding again before the tetromino is locked does nothing.
+        run_loop(&mut game, &TestGamePad::default());
+        run_loop(&mut game, &TestGamePad::pressed(Button::L));
+        assert_eq!(active_shape(&game), second);
+        assert_eq!(held_shape(&game), Some(first));
+
+        // Once locked, the held tetromino can be swapped back in.
+        run_loop(&mut game, &TestGamePad::pressed(Button::B));
+        start_loops_until_spawn(&mut game);
+        let third = active_shape(&game);
+        run_loop(&mut game, &TestGamePad::pressed(Button::L));
+        assert_eq!(active_shape(&game), first);
+        assert_eq!(held_shape(&game), Some(third));
+    }
+
+    #[test]
+    fn hold_can_be_disabled() {
+        let settings = TetrisSettings {
+            enable_hold: false,
+            ..Default::default()
+        };
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        let first = active_shape(&game);
+        run_loop(&mut game, &TestGamePad::pressed(Button::L));
+        assert_eq!(active_shape(&game), first);
+        assert_eq!(held_shape(&game), None);
+    }
+
+    #[test]
+    fn next_queue_length_is_clamped() {
+        for (next_queue_length, expected) in [(0, 1), (3, 3), (10, 6)] {
+            let settings = TetrisSettings {
+                next_queue_length,
+                ..Default::default()
+            };
+            let mut game = create_game(&settings);
+            start_loops_until_spawn(&mut game);
+            assert_eq!(game.next_tetrominoes.len(), expected);
+        }
+    }
+
+    #[test]
+    fn spawn_after_entry_delay() {
+        for entry_delay in [0, 4] {
+            let settings = TetrisSettings {
+                entry_delay,
+                ..Default::default()
+            };
+            let mut game = create_game(&settings);
+            assert_eq!(start_loops_until_spawn(&mut game), entry_delay + 1);
+
+            // Same after a tetromino is locked.
+            run_loop(&mut game, &TestGamePad::pressed(Button::B));
+            assert!(game.active_tetromino.is_none());
+            assert_eq!(start_loops_until_spawn(&mut game), entry_delay + 1);
+        }
+    }
+
+    #[test]
+    fn destroy_rows_after_line_clear_delay() {
+        for line_clear_delay in [0, 12] {
+            let settings = TetrisSettings {
+                line_clear_delay,
+                ..Default::default()
+            };
+            let mut game = create_game(&settings);
+            start_loops_until_spawn(&mut game);
+            // Hard drop an I tetromino into the hole of the bottom row.
+            let tetromino = Tetromino::new(Shape::I, game.top_center_pos(), game.rotation_system);
+            let bottom = game.play_field.height() - 1;
+            let hole: Vec<i16> = tetromino.bricks().iter().map(|b| b.xy().0).collect();
+            let row: Vec<Position> = (0..game.play_field.width())
+                .filter(|x| !hole.contains(x))
+                .map(|x| Position::new(x, bottom))
+                .collect();
+            game.play_field.fill_space(&row, Color::Gray);
+            game.active_tetromino = Some(tetromino);
+            run_loop(&mut game, &TestGamePad::pressed(Button::B));
+
+            let corner = [Position::new(0, bottom)];
+            for _ in 0..line_clear_delay {
+                game.start_loop();
+                assert!(!game.play_field.is_free(&corner));
+                assert!(game.active_tetromino.is_none());
+            }
+            game.start_loop();
+            assert!(game.play_field.is_free(&corner));
+        }
+    }
+
+    /// Run game loops with the given buttons held, returning the number of
+    /// rows the active tetromino has fallen by.
+    fn rows_fallen(game: &mut Ongoing, buttons: &[Button], n_loops: u16) -> i16 {
+        let bottom_row = game.active_tetromino.as_ref().unwrap().bottom_row();
+        let mut pad = TestGamePad::default();
+        for _ in 0..n_loops {
+            pad.hold(buttons);
+            run_loop(game, &pad);
+        }
+        game.active_tetromino.as_ref().unwrap().bottom_row() - bottom_row
+    }
+
+    #[test]
+    fn soft_drop_multiplies_gravity() {
+        // A quarter of a row per loop, twice as much when soft dropping.
+        let settings = TetrisSettings {
+            gravity: vec![0.25],
+            loops_per_second: 60,
+            soft_drop_factor: 2,
+            ..Default::default()
+        };
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        assert_eq!(rows_fallen(&mut game, &[], 8), 2);
+        assert_eq!(rows_fallen(&mut game, &[Button::Down], 8), 4);
+    }
+
+    #[test]
+    fn soft_drop_scores_per_row() {
+        let settings = TetrisSettings {
+            gravity: vec![0.25],
+            loops_per_second: 60,
+            soft_drop_factor: 2,
+            ..Default::default()
+        };
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        rows_fallen(&mut game, &[], 8);
+        assert_eq!(game.score, 0);
+        rows_fallen(&mut game, &[Button::Down], 8);
+        assert_eq!(game.score, 4);
+    }
+
+    #[test]
+    fn sonic_drop_does_not_lock() {
+        let settings = TetrisSettings {
+            soft_drop_factor: 0,
+            ..Default::default()
+        };
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        let n_rows = rows_fallen(&mut game, &[Button::Down], 1);
+        let tetromino = game.active_tetromino.as_ref().unwrap();
+        assert_eq!(tetromino.bottom_row(), game.play_field.height() - 1);
+        assert_eq!(game.score, n_rows as u32);
+        // It locks only when the lock delay expires, counting the loop it landed in.
+        assert_eq!(rows_fallen(&mut game, &[], settings.lock_delay - 2), 0);
+        run_loop(&mut game, &TestGamePad::default());
+        assert!(game.active_tetromino.is_none());
+    }
+
+    #[test]
+    fn pause_freezes_the_lock_delay() {
+        let settings = TetrisSettings {
+            soft_drop_factor: 0,
+            ..Default::default()
+        };
+        let mut game = create_game(&settings);
+        start_loops_until_spawn(&mut game);
+        // Sonic drop, then spend some of the lock delay on the ground.
+        rows_fallen(&mut game, &[Button::Down], 1);
+        rows_fallen(&mut game, &[], 5);
+
+        // While paused, only the pause state on top runs.
+        game.pause();
+        assert!(matches!(
+            game.end_loop(),
+            Some(Transition::Push(StateName::Pause))
+        ));
+        let mut pause = Pause::new(&settings);
+        for _ in 0..100 {
+            pause.start_loop();
+            pause.process_input(&TestGamePad::default());
+            pause.update();
+            assert!(pause.end_loop().is_none());
+        }
+        pause.process_input(&TestGamePad::pressed(Button::Start));
+        assert!(matches!(pause.end_loop(), Some(Transition::Pop)));
+
+        // The lock delay goes on where it stopped.
+        rows_fallen(&mut game, &[], settings.lock_delay - 7);
+        run_loop(&mut game, &TestGamePad::default());
+        assert!(game.active_tetromino.is_none());
+    }
+
+    #[test]
+    fn start_from_chosen_level() {
+        let settings = TetrisSettings {
+            level_progression: LevelProgressionName::Score,
+            score_per_level: 1000,
+            ..Default::default()
+        };
+        let options = GameOptions {
+            start_level: 5,
+            ..GameOptions::new(&settings)
+        };
+        let mut game = Ongoing::with_rng(&settings, options, create_rng(Some(0)));
+        assert_eq!(game.level(), 5);
+
+        // No level-up until the score of level 6.
+        for (score, level) in [(5999, 5), (6000, 6)] {
+            start_loops_until_spawn(&mut game);
+            game.score = score;
+            run_loop(&mut game, &TestGamePad::pressed(Button::B));
+            assert_eq!(game.level(), level);
+        }
+    }
+
+    #[test]
+    fn ultra_ends_on_time() {
+        let settings = TetrisSettings {
+            ultra_seconds: 2,
+            ..Default::default()
+        };
+        let options = GameOptions {
+            mode: GameMode::Ultra,
+            ..GameOptions::new(&settings)
+        };
+        let mut game = Ongoing::with_rng(&settings, options, create_rng(Some(0)));
+        for _ in 1..2 * settings.loops_per_second {
+            game.start_loop();
+            game.process_input(&TestGamePad::default());
+            game.update();
+            assert!(!game.is_game_over());
+        }
+        game.start_loop();
+        assert!(game.is_game_over());
+        let results = game.results();
+        assert!(results.is_completed);
+        assert_eq!(results.n_loops, 80);
+    }
+}
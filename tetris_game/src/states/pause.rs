@@ -0,0 +1,43 @@
+use crate::conf::TetrisSettings;
+use crate::{Button, GamePad, GameUI, Position};
+
+use super::{State, Transition};
+
+/// The game paused. The play field is hidden so that pausing cannot be used
+/// to think ahead.
+pub struct Pause {
+    is_resumed: bool,
+}
+
+impl Pause {
+    pub fn new(_settings: &TetrisSettings) -> Self {
+        Self { is_resumed: false }
+    }
+}
+
+impl State for Pause {
+    fn start_loop(&mut self) {}
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        if pad.is_pressed(Button::Pause) || pad.is_pressed(Button::Start) {
+            log::info!("Resuming game");
+            self.is_resumed = true;
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        ui.draw_text(Position::new(5, 8), "Paused");
+        ui.draw_text(Position::new(5, 10), "Press Start to resume");
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        if self.is_resumed {
+            log::info!("Transitioning state: Pause to Ongoing");
+            Some(Transition::Pop)
+        } else {
+            None
+        }
+    }
+}
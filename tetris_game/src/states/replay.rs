@@ -0,0 +1,123 @@
+use crate::conf::TetrisSettings;
+use crate::replay::Replay;
+use crate::{Button, GamePad, GameUI, Position};
+
+use super::ongoing::Ongoing;
+use super::{State, Transition};
+
+/// Play a recorded game again, feeding its recorded input to a new game
+/// instead of the input of the player, who can only stop the replay.
+pub struct ReplayViewer<'a> {
+    settings: &'a TetrisSettings,
+    game: Ongoing<'a>,
+    replay: Box<Replay>,
+    index: usize,
+    is_stopped: bool,
+}
+
+impl<'a> ReplayViewer<'a> {
+    pub fn new(settings: &'a TetrisSettings, replay: Box<Replay>) -> Self {
+        Self {
+            settings,
+            game: Ongoing::with_rng(settings, replay.options(), replay.rng()),
+            replay,
+            index: 0,
+            is_stopped: false,
+        }
+    }
+}
+
+impl<'a> State for ReplayViewer<'a> {
+    fn start_loop(&mut self) {
+        self.game.start_loop();
+    }
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        if pad.is_pressed(Button::Start) {
+            log::info!("Stopping replay");
+            self.is_stopped = true;
+        }
+        // The game ignores the input once it is over, which is when the
+        // recording ends.
+        if !self.game.is_game_over() {
+            if let Some(replay_pad) = self.replay.pad(self.index) {
+                self.game.process_input(&replay_pad);
+                self.index += 1;
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        self.game.update();
+    }
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        self.game.draw(ui);
+        let text_x = i16::from(self.settings.play_field_width) + 4;
+        ui.draw_text(Position::new(text_x, 14), "REPLAY");
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        // The replayed game may ask for transitions (e.g. the recorded pause),
+        // which are ignored.
+        self.game.end_loop();
+        if self.is_stopped {
+            log::info!("Transitioning state: Replay to Results");
+            Some(Transition::Pop)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::common::TestGamePad;
+    use crate::mode::GameOptions;
+    use crate::random::{create_rng, GameRng};
+
+    /// Hold random buttons for the next game loop, for a random game.
+    fn hold_random_buttons(pad: &mut TestGamePad, rng: &mut GameRng) {
+        let buttons: Vec<Button> = Button::ALL
+            .into_iter()
+            // Never pause nor give up the game.
+            .filter(|button| !matches!(button, Button::Pause | Button::Start))
+            .filter(|_| rng.gen_ratio(1, 4))
+            .collect();
+        pad.hold(&buttons);
+    }
+
+    #[test]
+    fn replay_plays_the_same_game() {
+        let settings = TetrisSettings::default();
+        let options = GameOptions::default();
+        let mut game = Ongoing::new(&settings, options, &mut create_rng(Some(0)));
+        let mut pad = TestGamePad::default();
+        let mut pad_rng = create_rng(Some(1));
+        for _ in 0..5000 {
+            hold_random_buttons(&mut pad, &mut pad_rng);
+            game.start_loop();
+            game.process_input(&pad);
+            game.update();
+            game.end_loop();
+        }
+        let results = game.results();
+        assert!(results.statistics.n_pieces > 10);
+
+        let mut viewer = ReplayViewer::new(&settings, Box::new(results.replay.clone()));
+        let no_input = TestGamePad::default();
+        for _ in 0..5000 {
+            viewer.start_loop();
+            viewer.process_input(&no_input);
+            viewer.update();
+            viewer.end_loop();
+        }
+        let replayed_results = viewer.game.results();
+        assert_eq!(replayed_results.score, results.score);
+        assert_eq!(replayed_results.n_loops, results.n_loops);
+        assert_eq!(replayed_results.statistics, results.statistics);
+    }
+}
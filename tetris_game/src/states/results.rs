@@ -0,0 +1,205 @@
+use crate::conf::TetrisSettings;
+use crate::highscores::HighScore;
+use crate::mode::{GameMode, GameOptions};
+use crate::replay::Replay;
+use crate::statistics::Statistics;
+use crate::{Button, GamePad, GameUI, Position};
+
+use super::{State, StateName, Transition};
+
+/// The outcome of a finished game.
+pub struct GameResults {
+    pub options: GameOptions,
+    pub score: u32,
+    pub level: u8,
+    /// Duration of the game in game loops.
+    pub n_loops: u32,
+    pub statistics: Statistics,
+    pub replay: Replay,
+    /// Whether the goal of the mode was reached, e.g. the lines of a Sprint,
+    /// rather than topping out.
+    pub is_completed: bool,
+}
+
+impl GameResults {
+    /// Whether the game can make it into the high scores: a Sprint only
+    /// counts if all its lines were cleared.
+    pub fn is_ranked(&self) -> bool {
+        self.options.mode != GameMode::Sprint || self.is_completed
+    }
+
+    /// The entry of the game in the high scores, timed at the given rate.
+    pub fn high_score(&self, loops_per_second: u16) -> HighScore {
+        HighScore {
+            score: self.score,
+            level: self.level,
+            n_lines: self.statistics.n_lines,
+            centis: to_centis(self.n_loops, loops_per_second),
+        }
+    }
+}
+
+/// Convert a number of game loops into hundredths of a second.
+pub fn to_centis(n_loops: u32, loops_per_second: u16) -> u32 {
+    let centis = u64::from(n_loops) * 100 / u64::from(loops_per_second.max(1));
+    centis.min(u64::from(u32::MAX)) as u32
+}
+
+/// Format a number of game loops as a duration, e.g. "1:05.25".
+pub fn format_time(n_loops: u32, loops_per_second: u16) -> String {
+    format_centis(to_centis(n_loops, loops_per_second))
+}
+
+/// Format hundredths of a second as a duration, e.g. "1:05.25".
+pub fn format_centis(centis: u32) -> String {
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+/// What the player can do from the results screen.
+const OPTIONS: [&str; 3] = ["Retry", "Watch replay", "Main menu"];
+
+/// The results screen shown after the game is over.
+pub struct Results {
+    results: Box<GameResults>,
+    /// Rank of the game in the high scores (from 0), if it made it.
+    rank: Option<usize>,
+    loops_per_second: u16,
+    selected: usize,
+    is_chosen: bool,
+}
+
+impl Results {
+    pub fn new(settings: &TetrisSettings, results: Box<GameResults>, rank: Option<usize>) -> Self {
+        Self {
+            results,
+            rank,
+            loops_per_second: settings.loops_per_second,
+            selected: 0,
+            is_chosen: false,
+        }
+    }
+
+    /// Pieces per second.
+    fn pps(&self) -> f32 {
+        let seconds = self.results.n_loops as f32 / f32::from(self.loops_per_second.max(1));
+        if seconds > 0.0 {
+            self.results.statistics.n_pieces as f32 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl State for Results {
+    fn start_loop(&mut self) {}
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        if pad.is_pressed(Button::Up) {
+            self.selected = (self.selected + OPTIONS.len() - 1) % OPTIONS.len();
+        }
+        if pad.is_pressed(Button::Down) {
+            self.selected = (self.selected + 1) % OPTIONS.len();
+        }
+        if pad.is_pressed(Button::Start) || pad.is_pressed(Button::A) {
+            self.is_chosen = true;
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        let results = &self.results;
+        let statistics = &results.statistics;
+        ui.draw_text(
+            Position::new(1, 2),
+            &format!("Results - {}", results.options.mode.name()),
+        );
+        if let Some(rank) = self.rank {
+            ui.draw_text(
+                Position::new(1, 3),
+                &format!("New high score: #{}", rank + 1),
+            );
+        } else if !results.is_ranked() {
+            ui.draw_text(Position::new(1, 3), "Not completed");
+        }
+
+        let lines = [
+            format!("Score: {}", results.score),
+            format!("Level: {}", results.level),
+            format!("Lines: {}", statistics.n_lines),
+            format!(
+                "Time: {}",
+                format_time(results.n_loops, self.loops_per_second)
+            ),
+            format!("Pieces: {}", statistics.n_pieces),
+            format!("PPS: {:.2}", self.pps()),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            ui.draw_text(Position::new(1, 4 + i as i16), line);
+        }
+
+        let [n_singles, n_doubles, n_triples, n_tetrises] = statistics.n_clears;
+        let clears = [
+            format!("Singles: {}", n_singles),
+            format!("Doubles: {}", n_doubles),
+            format!("Triples: {}", n_triples),
+            format!("Tetrises: {}", n_tetrises),
+            format!("T-spins: {}", statistics.n_t_spins),
+            format!("Perfect clears: {}", statistics.n_perfect_clears),
+        ];
+        for (i, line) in clears.iter().enumerate() {
+            ui.draw_text(Position::new(12, 4 + i as i16), line);
+        }
+
+        for (i, option) in OPTIONS.iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            ui.draw_text(
+                Position::new(5, 12 + i as i16),
+                &format!("{} {}", marker, option),
+            );
+        }
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        if !self.is_chosen {
+            return None;
+        }
+        self.is_chosen = false;
+        match self.selected {
+            0 => {
+                log::info!("Transitioning state: Results to Ongoing");
+                let options = self.results.options;
+                Some(Transition::Replace(StateName::Ongoing(options)))
+            }
+            1 => {
+                log::info!("Transitioning state: Results to Replay");
+                let replay = Box::new(self.results.replay.clone());
+                Some(Transition::Push(StateName::Replay(replay)))
+            }
+            _ => {
+                log::info!("Transitioning state: Results to Menu");
+                Some(Transition::Replace(StateName::Menu))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_format() {
+        assert_eq!(format_time(0, 40), "0:00.00");
+        assert_eq!(format_time(2610, 40), "1:05.25");
+        assert_eq!(format_time(60 * 60 * 12 + 30, 60), "12:00.50");
+        assert_eq!(format_centis(6525), "1:05.25");
+        // The same duration at different rates.
+        assert_eq!(to_centis(2610, 40), to_centis(3915, 60));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::conf::TetrisSettings;
+use crate::{Button, GamePad, GameUI, Position};
+
+use super::{State, Transition};
+
+/// The main settings, shown from the main menu. They are changed by editing
+/// the settings file of the front end.
+pub struct SettingsView<'a> {
+    settings: &'a TetrisSettings,
+    is_closed: bool,
+}
+
+impl<'a> SettingsView<'a> {
+    pub fn new(settings: &'a TetrisSettings) -> Self {
+        Self {
+            settings,
+            is_closed: false,
+        }
+    }
+}
+
+impl<'a> State for SettingsView<'a> {
+    fn start_loop(&mut self) {}
+
+    fn process_input(&mut self, pad: &dyn GamePad) {
+        if pad.is_pressed(Button::Start) || pad.is_pressed(Button::A) || pad.is_pressed(Button::B) {
+            self.is_closed = true;
+        }
+    }
+
+    fn update(&mut self) {}
+
+    fn draw(&self, ui: &mut dyn GameUI) {
+        let settings = self.settings;
+        ui.draw_text(Position::new(1, 2), "Settings");
+        let lines = [
+            format!(
+                "Play field: {} x {}",
+                settings.play_field_width, settings.play_field_height
+            ),
+            format!("Scoring: {:?}", settings.scoring),
+            format!("Levels: {:?}", settings.level_progression),
+            format!("Randomizer: {:?}", settings.randomizer),
+            format!("Rotation system: {:?}", settings.rotation_system),
+            format!("Hold: {}", settings.enable_hold),
+            format!("Next queue: {}", settings.next_queue_length),
+            format!("Ghost: {}", settings.show_ghost),
+            format!("Lock reset: {:?}", settings.lock_reset_policy),
+            format!(
+                "DAS / ARR: {} / {} loops",
+                settings.auto_shift_delay, settings.auto_repeat_rate
+            ),
+            format!("Soft drop factor: {}", settings.soft_drop_factor),
+            format!("Top out: {:?}", settings.top_out_rule),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            ui.draw_text(Position::new(1, 4 + i as i16), line);
+        }
+        ui.draw_text(
+            Position::new(1, 17),
+            "Edit the settings file to change them",
+        );
+        ui.draw_text(Position::new(1, 19), "Press Start to go back");
+    }
+
+    fn end_loop(&mut self) -> Option<Transition> {
+        if self.is_closed {
+            Some(Transition::Pop)
+        } else {
+            None
+        }
+    }
+}
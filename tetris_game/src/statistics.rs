@@ -0,0 +1,68 @@
+use crate::scoring::Clear;
+use crate::tspin::TSpin;
+
+/// Counters of what happened during a game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// Number of tetrominoes locked into the play field.
+    pub n_pieces: u32,
+    /// Number of rows cleared.
+    pub n_lines: u32,
+    /// Number of singles, doubles, triples and tetrises (T-spins included).
+    pub n_clears: [u32; 4],
+    /// Number of T-spins (minis included), with or without clear.
+    pub n_t_spins: u32,
+    /// Number of clears leaving the play field empty.
+    pub n_perfect_clears: u32,
+}
+
+impl Statistics {
+    /// Count a locked tetromino and what it cleared.
+    pub fn record(&mut self, clear: Clear) {
+        self.n_pieces += 1;
+        self.n_lines += clear.n_rows;
+        if clear.n_rows > 0 {
+            let index = (clear.n_rows as usize).min(self.n_clears.len()) - 1;
+            self.n_clears[index] += 1;
+        }
+        if clear.t_spin != TSpin::None {
+            self.n_t_spins += 1;
+        }
+        if clear.is_perfect_clear {
+            self.n_perfect_clears += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let mut statistics = Statistics::default();
+        let clears = [
+            (0, TSpin::None, false),
+            (2, TSpin::Full, false),
+            (4, TSpin::None, true),
+            (1, TSpin::Mini, false),
+        ];
+        for (n_rows, t_spin, is_perfect_clear) in clears {
+            statistics.record(Clear {
+                n_rows,
+                t_spin,
+                is_perfect_clear,
+            });
+        }
+        assert_eq!(
+            statistics,
+            Statistics {
+                n_pieces: 4,
+                n_lines: 7,
+                n_clears: [1, 1, 0, 1],
+                n_t_spins: 2,
+                n_perfect_clears: 1,
+            }
+        );
+    }
+}
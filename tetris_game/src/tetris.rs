@@ -1,40 +1,179 @@
 use crate::common::{GamePad, GameUI};
 use crate::conf::TetrisSettings;
-use crate::states::{create_state, State, StateName};
+use crate::highscores::HighScores;
+use crate::random::{create_rng, GameRng};
+use crate::states::{create_state, State, StateName, Transition};
 
 pub struct Tetris<'a> {
     settings: &'a TetrisSettings,
-    state: Box<dyn State + 'a>,
+    rng: GameRng,
+    /// The stack of states, the current one on top. Only the current state
+    /// runs; the ones underneath are kept as is until they are back on top.
+    states: Vec<Box<dyn State + 'a>>,
+    high_scores: HighScores,
+    is_quit: bool,
 }
 
 impl<'a> Tetris<'a> {
-    pub fn new(settings: &'a TetrisSettings) -> Self {
+    pub fn new(settings: &'a TetrisSettings, high_scores: HighScores) -> Self {
+        let mut rng = create_rng(settings.seed);
+        let state = create_state(StateName::Menu, settings, &mut rng, &high_scores);
         Self {
             settings,
-            state: create_state(StateName::Intro, settings),
+            rng,
+            states: vec![state],
+            high_scores,
+            is_quit: false,
+        }
+    }
+
+    /// The best games, to be saved by the front end.
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// Whether the player has chosen to quit the game.
+    pub fn is_quit(&self) -> bool {
+        self.is_quit
+    }
+
+    fn create_state(&mut self, name: StateName) -> Box<dyn State + 'a> {
+        create_state(name, self.settings, &mut self.rng, &self.high_scores)
+    }
+
+    fn state(&self) -> &dyn State {
+        match self.states.last() {
+            Some(state) => state.as_ref(),
+            None => panic!("Impossible!"),
+        }
+    }
+
+    fn state_mut(&mut self) -> &mut (dyn State + 'a) {
+        match self.states.last_mut() {
+            Some(state) => state.as_mut(),
+            None => panic!("Impossible!"),
         }
     }
 
     pub fn start_loop(&mut self) {
-        self.state.start_loop();
+        self.state_mut().start_loop();
     }
 
     pub fn process_input(&mut self, pad: &dyn GamePad) {
-        self.state.process_input(pad);
+        self.state_mut().process_input(pad);
     }
 
     pub fn update(&mut self) {
-        self.state.update();
+        self.state_mut().update();
     }
 
     pub fn draw(&self, ui: &mut dyn GameUI) {
-        self.state.draw(ui);
+        self.state().draw(ui);
+    }
+
+    /// Pause the game right away if it is ongoing, e.g. when the front end
+    /// has not been able to run the game for a while.
+    pub fn pause(&mut self) {
+        self.state_mut().pause();
+        self.end_loop();
     }
 
     pub fn end_loop(&mut self) {
-        let next_state_name = self.state.end_loop();
-        if let Some(state_name) = next_state_name {
-            self.state = create_state(state_name, self.settings);
+        let transition = self.state_mut().end_loop();
+        match transition {
+            Some(Transition::Push(state_name)) => {
+                let state = self.create_state(state_name);
+                self.states.push(state);
+            }
+            Some(Transition::Pop) => {
+                // The bottom state cannot be popped: there would be nothing to run.
+                if self.states.len() > 1 {
+                    self.states.pop();
+                } else {
+                    log::error!("Cannot pop the last state");
+                }
+            }
+            Some(Transition::Replace(state_name)) => {
+                let state = self.create_state(state_name);
+                self.states.pop();
+                self.states.push(state);
+            }
+            Some(Transition::GameOver(results)) => {
+                let rank = if results.is_ranked() {
+                    let mode = results.options.mode;
+                    let entry = results.high_score(self.settings.loops_per_second);
+                    self.high_scores.insert(mode, entry)
+                } else {
+                    None
+                };
+                let state = self.create_state(StateName::Results(results, rank));
+                self.states.pop();
+                self.states.push(state);
+            }
+            Some(Transition::Quit) => {
+                self.is_quit = true;
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Button, TestGamePad};
+    use crate::mode::GameMode;
+
+    fn run_loop(tetris: &mut Tetris, pad: &TestGamePad) {
+        tetris.start_loop();
+        tetris.process_input(pad);
+        tetris.update();
+        tetris.end_loop();
+    }
+
+    #[test]
+    fn pause_is_pushed_on_top_of_the_game() {
+        let settings = TetrisSettings {
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tetris = Tetris::new(&settings, HighScores::default());
+        // Start a game from the menu.
+        run_loop(&mut tetris, &TestGamePad::pressed(Button::Start));
+        assert_eq!(tetris.states.len(), 1);
+
+        tetris.pause();
+        assert_eq!(tetris.states.len(), 2);
+        run_loop(&mut tetris, &TestGamePad::default());
+        assert_eq!(tetris.states.len(), 2);
+
+        run_loop(&mut tetris, &TestGamePad::pressed(Button::Pause));
+        assert_eq!(tetris.states.len(), 1);
+    }
+
+    #[test]
+    fn game_over_is_recorded_once() {
+        let settings = TetrisSettings {
+            seed: Some(0),
+            ..Default::default()
+        };
+        let mut tetris = Tetris::new(&settings, HighScores::default());
+        run_loop(&mut tetris, &TestGamePad::pressed(Button::Start));
+        // Hard drop until topping out, then go to the results.
+        let mut n_loops = 0;
+        while tetris.high_scores().entries(GameMode::Marathon).is_empty() {
+            assert!(n_loops < 10000, "The game should be over");
+            let button = if n_loops % 2 == 0 {
+                Button::B
+            } else {
+                Button::Start
+            };
+            run_loop(&mut tetris, &TestGamePad::pressed(button));
+            n_loops += 1;
+        }
+        for _ in 0..100 {
+            run_loop(&mut tetris, &TestGamePad::default());
         }
+        assert_eq!(tetris.high_scores().entries(GameMode::Marathon).len(), 1);
     }
 }
@@ -1,11 +1,12 @@
 use super::common::{Color, Position};
+use super::rotation::RotationSystem;
 
 /// A trait allowing the tetromino to query the game world (play field).
 pub trait GameWorld {
     fn is_free(&self, positions: &[Position]) -> bool;
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Shape {
     I,
     O,
@@ -17,18 +18,15 @@ pub enum Shape {
 }
 
 impl Shape {
-    pub fn pick(n: u8) -> Self {
-        match n % 7 {
-            0 => Shape::I,
-            1 => Shape::O,
-            2 => Shape::T,
-            3 => Shape::J,
-            4 => Shape::L,
-            5 => Shape::S,
-            6 => Shape::Z,
-            _ => panic!("Impossible!"),
-        }
-    }
+    pub const ALL: [Shape; 7] = [
+        Shape::I,
+        Shape::O,
+        Shape::T,
+        Shape::J,
+        Shape::L,
+        Shape::S,
+        Shape::Z,
+    ];
 
     fn color(&self) -> Color {
         // TODO: Use different colors.
@@ -42,129 +40,34 @@ impl Shape {
             Shape::Z => Color::Red,
         }
     }
-
-    fn get_bricks(&self, position: Position, degree: i16) -> Vec<Position> {
-        let (x, y) = position.xy();
-        match self {
-            Shape::I => {
-                if degree == 0 || degree == 180 {
-                    // Start horizentally, to give player a bit more chance...
-                    vec![
-                        Position::new(x, y),
-                        Position::new(x + 1, y),
-                        Position::new(x + 2, y),
-                        Position::new(x + 3, y),
-                    ]
-                } else {
-                    vec![
-                        Position::new(x, y),
-                        Position::new(x, y + 1),
-                        Position::new(x, y + 2),
-                        Position::new(x, y + 3),
-                    ]
-                }
-            }
-            Shape::O => {
-                vec![
-                    Position::new(x, y),
-                    Position::new(x, y + 1),
-                    Position::new(x + 1, y),
-                    Position::new(x + 1, y + 1),
-                ]
-            }
-            Shape::T => rotate_3x3(
-                position,
-                vec![
-                    Position::new(0, 0),
-                    Position::new(1, 0),
-                    Position::new(1, 1),
-                    Position::new(2, 0),
-                ],
-                degree,
-            ),
-            Shape::J => rotate_3x3(
-                position,
-                vec![
-                    Position::new(2, 0),
-                    Position::new(2, 1),
-                    Position::new(2, 2),
-                    Position::new(1, 2),
-                ],
-                degree,
-            ),
-            Shape::L => rotate_3x3(
-                position,
-                vec![
-                    Position::new(0, 0),
-                    Position::new(0, 1),
-                    Position::new(0, 2),
-                    Position::new(1, 2),
-                ],
-                degree,
-            ),
-            Shape::S => rotate_3x3(
-                position,
-                vec![
-                    Position::new(0, 1),
-                    Position::new(1, 0),
-                    Position::new(1, 1),
-                    Position::new(2, 0),
-                ],
-                degree,
-            ),
-            Shape::Z => rotate_3x3(
-                position,
-                vec![
-                    Position::new(0, 0),
-                    Position::new(1, 0),
-                    Position::new(1, 1),
-                    Position::new(2, 1),
-                ],
-                degree,
-            ),
-        }
-    }
-}
-
-fn rotate_3x3(top_left: Position, bricks: Vec<Position>, degree: i16) -> Vec<Position> {
-    let (top_left_x, top_left_y) = top_left.xy();
-    let n_times = degree / 90;
-    bricks
-        .into_iter()
-        // Rotate right the relative positions of the bricks (in a 3x3 space) n times
-        .map(|pos| {
-            let (mut x, mut y) = pos.xy();
-            for _ in 0..n_times {
-                let (new_x, new_y) = (2 - y, x);
-                (x, y) = (new_x, new_y);
-            }
-            Position::new(x, y)
-        })
-        // Compute the absolute positions of the bricks
-        .map(|pos| {
-            let (dx, dy) = pos.xy();
-            Position::new(top_left_x + dx, top_left_y + dy)
-        })
-        .collect()
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Tetromino {
     shape: Shape,
-    position: Position, // top-left corner
-    degree: i16,        // 0, 90, 180, 270
+    position: Position, // top-left corner of the bounding box
+    rotation: u8,       // 0 (spawn), 1 (R), 2 (reverse), 3 (L)
     bricks: Vec<Position>,
+    rotation_system: &'static dyn RotationSystem,
+    // If the last successful action was a rotation, the index of the wall kick used.
+    last_kick: Option<usize>,
 }
 
 impl Tetromino {
-    pub fn new(shape: Shape, position: Position) -> Self {
-        let degree = 0;
-        let bricks = shape.get_bricks(position, degree);
+    pub fn new(
+        shape: Shape,
+        position: Position,
+        rotation_system: &'static dyn RotationSystem,
+    ) -> Self {
+        let rotation = 0;
+        let bricks = get_bricks(rotation_system, shape, position, rotation);
         Self {
             shape,
             position,
-            degree,
+            rotation,
             bricks,
+            rotation_system,
+            last_kick: None,
         }
     }
 
@@ -176,10 +79,45 @@ impl Tetromino {
         self.shape.color()
     }
 
+    #[cfg(test)]
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    #[cfg(test)]
+    pub fn rotation(&self) -> u8 {
+        self.rotation
+    }
+
     pub fn bricks(&self) -> &[Position] {
         &self.bricks
     }
 
+    /// If the last successful action was a rotation (and not a move or a
+    /// fall), the index of the wall kick used by the rotation.
+    pub fn last_kick(&self) -> Option<usize> {
+        self.last_kick
+    }
+
+    /// The row of the lowest brick.
+    pub fn bottom_row(&self) -> i16 {
+        self.bricks
+            .iter()
+            .map(|brick| brick.xy().1)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Whether the tetromino is on the ground, i.e. it cannot fall down anymore.
+    pub fn is_on_ground(&self, world: &dyn GameWorld) -> bool {
+        let bricks_below: Vec<Position> = self
+            .bricks
+            .iter()
+            .map(|brick| brick.updated((0, 1)))
+            .collect();
+        !world.is_free(&bricks_below)
+    }
+
     // TODO: Use `Result<(), Err>`?
     pub fn fall_down(&mut self, world: &dyn GameWorld) -> bool {
         self.move_towards((0, 1), world)
@@ -190,35 +128,90 @@ impl Tetromino {
         // Cannot move up so `dy` must be non-negative.
         let direction = (direction.0, direction.1.max(0));
         let next_position = self.position.updated(direction);
-        let next_bricks = self.shape.get_bricks(next_position, self.degree);
+        let next_bricks = get_bricks(
+            self.rotation_system,
+            self.shape,
+            next_position,
+            self.rotation,
+        );
         if world.is_free(&next_bricks) {
             self.position = next_position;
             self.bricks = next_bricks;
+            self.last_kick = None;
             true
         } else {
             false
         }
     }
 
-    pub fn fall_to_bottom(&mut self, world: &dyn GameWorld) {
+    /// Keep moving downwards until it cannot be moved anymore, returning the
+    /// number of rows fallen.
+    pub fn fall_to_bottom(&mut self, world: &dyn GameWorld) -> u32 {
+        let mut n_rows = 0;
         loop {
-            // Keep moving downwards until it cannot be moved anymore.
             let has_moved = self.move_towards((0, 1), world);
             if !has_moved {
                 break;
             }
+            n_rows += 1;
         }
+        n_rows
     }
 
     pub fn rotate_right(&mut self, world: &dyn GameWorld) -> bool {
-        let next_degree = (self.degree + 90) % 360;
-        let next_bricks = self.shape.get_bricks(self.position, next_degree);
-        if world.is_free(&next_bricks) {
-            self.degree = next_degree;
-            self.bricks = next_bricks;
-            true
-        } else {
-            false
+        self.rotate(1, world)
+    }
+
+    pub fn rotate_left(&mut self, world: &dyn GameWorld) -> bool {
+        self.rotate(3, world)
+    }
+
+    pub fn rotate_180(&mut self, world: &dyn GameWorld) -> bool {
+        self.rotate(2, world)
+    }
+
+    /// Rotate the tetromino clockwise by the given number of quarter turns,
+    /// trying the wall kicks of the rotation system in order if the rotated
+    /// tetromino collides with the walls or the inactive bricks.
+    fn rotate(&mut self, n_turns: u8, world: &dyn GameWorld) -> bool {
+        let next_rotation = (self.rotation + n_turns) % 4;
+        let kicks = self.rotation_system.kicks(
+            self.shape,
+            self.rotation,
+            next_rotation,
+            self.position,
+            world,
+        );
+        for (kick_index, kick) in kicks.into_iter().enumerate() {
+            let next_position = self.position.updated(kick);
+            let next_bricks = get_bricks(
+                self.rotation_system,
+                self.shape,
+                next_position,
+                next_rotation,
+            );
+            if world.is_free(&next_bricks) {
+                self.position = next_position;
+                self.rotation = next_rotation;
+                self.bricks = next_bricks;
+                self.last_kick = Some(kick_index);
+                return true;
+            }
         }
+        false
     }
 }
+
+/// Compute the absolute positions of the bricks of a shape in a rotation state.
+pub fn get_bricks(
+    rotation_system: &dyn RotationSystem,
+    shape: Shape,
+    position: Position,
+    rotation: u8,
+) -> Vec<Position> {
+    rotation_system
+        .bricks(shape, rotation)
+        .into_iter()
+        .map(|offset| position.updated(offset))
+        .collect()
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::Position;
+
+/// The conditions ending the game when the stack reaches the top of the play
+/// field. Block out, when a new tetromino cannot spawn because its space is
+/// occupied, always ends the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopOutRule {
+    /// Only block out.
+    BlockOut,
+    /// Block out, or lock out: a tetromino is locked entirely in the hidden
+    /// rows above the visible play field.
+    LockOut,
+    /// Block out, or partial lock out: a tetromino is locked with any of its
+    /// bricks in the hidden rows.
+    PartialLockOut,
+}
+
+impl TopOutRule {
+    /// Whether locking a tetromino with the given bricks ends the game.
+    pub fn is_locked_out(&self, bricks: &[Position]) -> bool {
+        let is_hidden = |brick: &Position| brick.xy().1 < 0;
+        match self {
+            TopOutRule::BlockOut => false,
+            TopOutRule::LockOut => bricks.iter().all(is_hidden),
+            TopOutRule::PartialLockOut => bricks.iter().any(is_hidden),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_locked_out() {
+        let bricks_at = |rows: [i16; 4]| -> Vec<Position> {
+            rows.iter()
+                .enumerate()
+                .map(|(x, &y)| Position::new(x as i16, y))
+                .collect()
+        };
+        let visible = bricks_at([0, 0, 1, 1]);
+        let partly_hidden = bricks_at([-1, -1, 0, 0]);
+        let hidden = bricks_at([-2, -2, -1, -1]);
+
+        let expected = [
+            (TopOutRule::BlockOut, [false, false, false]),
+            (TopOutRule::LockOut, [false, false, true]),
+            (TopOutRule::PartialLockOut, [false, true, true]),
+        ];
+        for (rule, [is_visible_out, is_partly_hidden_out, is_hidden_out]) in expected {
+            assert_eq!(rule.is_locked_out(&visible), is_visible_out);
+            assert_eq!(rule.is_locked_out(&partly_hidden), is_partly_hidden_out);
+            assert_eq!(rule.is_locked_out(&hidden), is_hidden_out);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::common::Position;
+use crate::tetromino::{GameWorld, Shape, Tetromino};
+
+/// How a T tetromino was spun into place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full,
+}
+
+/// The index of the SRS kick (the 5th one, moving the T by 1 column and 2
+/// rows) which always makes a full T-spin.
+const TST_KICK_INDEX: usize = 4;
+
+/// Detect whether the T tetromino about to be locked was spun into place,
+/// using the 3-corner rule: the last successful action must be a rotation,
+/// and at least 3 of the 4 corners diagonal to the center of the T must be
+/// occupied (by bricks or walls). It is a full T-spin if both corners on the
+/// pointing side are occupied, or a mini T-spin otherwise.
+pub fn detect_t_spin(tetromino: &Tetromino, world: &dyn GameWorld) -> TSpin {
+    if tetromino.shape() != Shape::T {
+        return TSpin::None;
+    }
+    let Some(kick_index) = tetromino.last_kick() else {
+        return TSpin::None;
+    };
+    let Some((center, (dx, dy))) = center_and_direction(tetromino.bricks()) else {
+        return TSpin::None;
+    };
+
+    let is_occupied = |(cx, cy): (i16, i16)| !world.is_free(&[center.updated((cx, cy))]);
+    // The 2 corners on the pointing side, then the 2 corners on the flat side.
+    let (px, py) = (-dy, dx);
+    let front_corners = [(dx + px, dy + py), (dx - px, dy - py)];
+    let back_corners = [(-dx + px, -dy + py), (-dx - px, -dy - py)];
+    let n_front = front_corners
+        .into_iter()
+        .filter(|&c| is_occupied(c))
+        .count();
+    let n_back = back_corners.into_iter().filter(|&c| is_occupied(c)).count();
+    classify(n_front, n_back, kick_index)
+}
+
+fn classify(n_front_corners: usize, n_back_corners: usize, kick_index: usize) -> TSpin {
+    if n_front_corners + n_back_corners < 3 {
+        TSpin::None
+    } else if n_front_corners == 2 || kick_index == TST_KICK_INDEX {
+        TSpin::Full
+    } else {
+        TSpin::Mini
+    }
+}
+
+/// Find the center brick of a T (the one with 3 neighbors), and the direction
+/// it points to (from the center towards the brick opposite to no brick).
+fn center_and_direction(bricks: &[Position]) -> Option<(Position, (i16, i16))> {
+    const DIRECTIONS: [(i16, i16); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    let center = *bricks.iter().find(|brick| {
+        DIRECTIONS
+            .iter()
+            .filter(|&&d| bricks.contains(&brick.updated(d)))
+            .count()
+            == 3
+    })?;
+    let direction = DIRECTIONS.into_iter().find(|&(dx, dy)| {
+        bricks.contains(&center.updated((dx, dy))) && !bricks.contains(&center.updated((-dx, -dy)))
+    })?;
+    Some((center, direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playfield::PlayField;
+    use crate::rotation::{get_rotation_system, RotationSystemName};
+    use crate::Color;
+
+    fn fill_row_except(field: &mut PlayField, y: i16, holes: &[i16]) {
+        let positions: Vec<Position> = (0..field.width())
+            .filter(|x| !holes.contains(x))
+            .map(|x| Position::new(x, y))
+            .collect();
+        field.fill_space(&positions, Color::Gray);
+    }
+
+    fn t_tetromino(position: Position) -> Tetromino {
+        Tetromino::new(
+            Shape::T,
+            position,
+            get_rotation_system(RotationSystemName::Srs),
+        )
+    }
+
+    #[test]
+    fn t_spin_double() {
+        // A T-slot at the bottom, with an overhang at the top-left corner.
+        let mut field = PlayField::new(10, 20, 0);
+        fill_row_except(&mut field, 19, &[4]);
+        fill_row_except(&mut field, 18, &[3, 4, 5]);
+        field.fill_space(&[Position::new(3, 17)], Color::Gray);
+
+        let mut tetromino = t_tetromino(Position::new(3, 17));
+        assert!(tetromino.rotate_right(&field));
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::Full);
+
+        // Failing to fall does not cancel the T-spin, but moving does.
+        assert!(!tetromino.fall_down(&field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::Full);
+        assert!(tetromino.move_towards((0, 0), &field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::None);
+    }
+
+    #[test]
+    fn t_spin_mini() {
+        // Only one of the front corners is occupied.
+        let mut field = PlayField::new(10, 20, 0);
+        let mut tetromino = t_tetromino(Position::new(3, 17));
+        assert!(tetromino.rotate_left(&field));
+        field.fill_space(
+            &[
+                Position::new(5, 17),
+                Position::new(3, 19),
+                Position::new(5, 19),
+            ],
+            Color::Gray,
+        );
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::Mini);
+    }
+
+    #[test]
+    fn not_enough_corners() {
+        let mut field = PlayField::new(10, 20, 0);
+        field.fill_space(&[Position::new(3, 19), Position::new(5, 19)], Color::Gray);
+        let mut tetromino = t_tetromino(Position::new(3, 17));
+        assert!(tetromino.rotate_180(&field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::None);
+    }
+
+    #[test]
+    fn only_t_can_spin() {
+        let field = PlayField::new(10, 20, 0);
+        let mut tetromino = Tetromino::new(
+            Shape::S,
+            Position::new(0, 17),
+            get_rotation_system(RotationSystemName::Srs),
+        );
+        assert!(tetromino.rotate_right(&field));
+        assert_eq!(detect_t_spin(&tetromino, &field), TSpin::None);
+    }
+
+    #[test]
+    fn tst_kick_makes_a_full_t_spin() {
+        assert_eq!(classify(1, 2, 0), TSpin::Mini);
+        assert_eq!(classify(1, 2, TST_KICK_INDEX), TSpin::Full);
+        assert_eq!(classify(2, 1, 0), TSpin::Full);
+        assert_eq!(classify(1, 1, TST_KICK_INDEX), TSpin::None);
+    }
+}
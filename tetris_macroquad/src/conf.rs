@@ -1,6 +1,6 @@
 use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
-use tetris_game::TetrisSettings;
+use tetris_game::{HighScores, TetrisSettings};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Settings {
@@ -8,8 +8,20 @@ pub struct Settings {
     pub ui: UISettings,
     pub game_pad: GamePadSettings,
 
-    /// Interval in millis between game loops.
-    pub loop_interval_millis: u8,
+    /// Max number of game loops run in a single frame to catch up with the
+    /// loop rate. The extra loops are dropped.
+    pub max_loops_per_frame: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tetris: TetrisSettings::default(),
+            ui: UISettings::default(),
+            game_pad: GamePadSettings::default(),
+            max_loops_per_frame: 4,
+        }
+    }
 }
 
 impl Settings {
@@ -27,17 +39,6 @@ impl Settings {
     }
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            tetris: TetrisSettings::default(),
-            ui: UISettings::default(),
-            game_pad: GamePadSettings::default(),
-            loop_interval_millis: 25,
-        }
-    }
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UISettings {
     pub brick_size: u8,
@@ -76,3 +77,32 @@ pub fn load_settings() -> Result<Settings, ConfigError> {
         .build()?;
     settings.try_deserialize()
 }
+
+/// The file the high scores are saved into, in the working directory.
+const HIGH_SCORES_FILE: &str = "high_scores.json";
+
+/// Load the high scores saved by the previous sessions, if any.
+pub fn load_high_scores() -> HighScores {
+    let path = std::env::current_dir()
+        .expect("Fail to get current dir")
+        .join(HIGH_SCORES_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+            log::warn!("Fail to parse high scores {}: {}", path.display(), err);
+            HighScores::default()
+        }),
+        Err(_) => HighScores::default(),
+    }
+}
+
+pub fn save_high_scores(high_scores: &HighScores) {
+    let path = std::env::current_dir()
+        .expect("Fail to get current dir")
+        .join(HIGH_SCORES_FILE);
+    let result = serde_json::to_string_pretty(high_scores)
+        .map_err(|err| err.to_string())
+        .and_then(|json| std::fs::write(&path, json).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        log::warn!("Fail to save high scores {}: {}", path.display(), err);
+    }
+}
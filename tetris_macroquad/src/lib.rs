@@ -1,5 +1,6 @@
 mod conf;
 mod play;
+mod timestep;
 mod ui;
 
 pub use conf::{load_settings, Settings};
@@ -1,12 +1,18 @@
 use macroquad::prelude::*; // TODO: Should not depend on macroquad
-use std::thread::sleep;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant};
 
 use tetris_game::Tetris;
 
-use crate::conf::Settings;
+use crate::conf::{load_high_scores, save_high_scores, Settings};
+use crate::timestep::FixedTimestep;
 use crate::ui::{MacroquadGamePad, MacroquadUI};
 
+/// Pause the game when a frame stalls for longer than this, e.g. while the
+/// window is minimized or dragged around. This does not catch the window
+/// losing focus while still visible: macroquad does not report focus changes
+/// (the minimized/restored events of miniquad are not passed on to the game).
+const STALLED_FRAME: Duration = Duration::from_millis(500);
+
 pub async fn play_game(settings: Settings) {
     log::info!("Using settings: {:?}", settings);
     log::info!(
@@ -17,34 +23,42 @@ pub async fn play_game(settings: Settings) {
 
     let mut ui = MacroquadUI::new(&settings.ui);
     let mut pad = MacroquadGamePad::new(&settings.game_pad);
-    let mut tetris = Tetris::new(&settings.tetris);
+    let mut tetris = Tetris::new(&settings.tetris, load_high_scores());
+    let mut saved_high_scores = tetris.high_scores().clone();
 
     ui.clear_background();
 
-    let loop_interval = Duration::from_millis(settings.loop_interval_millis as u64);
-    let mut n_loops = 0;
-    let mut t = SystemTime::now();
+    let mut timestep = FixedTimestep::new(
+        settings.tetris.loops_per_second,
+        settings.max_loops_per_frame,
+    );
+    let mut last_frame = Instant::now();
     loop {
-        // `unwrap` is not safe as occasionally we can get `SystemTimeError` (I don't know why...).
-        let dt = SystemTime::now().duration_since(t).unwrap_or_default();
-        if loop_interval > dt {
-            let dt_to_sleep = loop_interval - dt;
-            sleep(dt_to_sleep);
-        } else {
-            let overrun_millis = (dt - loop_interval).as_millis();
-            if overrun_millis > 0 {
-                log::warn!("Loop #{} overran {} millis!", n_loops, overrun_millis);
-            }
+        let now = Instant::now();
+        let elapsed = now - last_frame;
+        let n_loops = timestep.advance(elapsed);
+        last_frame = now;
+        if elapsed > STALLED_FRAME {
+            log::info!("Frame stalled for {} millis: Pausing", elapsed.as_millis());
+            tetris.pause();
         }
-        n_loops += 1;
-        t = SystemTime::now();
 
-        tetris.start_loop();
-        pad.refresh_input();
-        tetris.process_input(&pad);
-        tetris.update();
+        pad.poll_input();
+        for _ in 0..n_loops {
+            tetris.start_loop();
+            pad.refresh_input();
+            tetris.process_input(&pad);
+            tetris.update();
+            tetris.end_loop();
+        }
+        if tetris.high_scores() != &saved_high_scores {
+            saved_high_scores = tetris.high_scores().clone();
+            save_high_scores(&saved_high_scores);
+        }
+        if tetris.is_quit() {
+            break;
+        }
         tetris.draw(&mut ui);
-        tetris.end_loop();
 
         next_frame().await
     }
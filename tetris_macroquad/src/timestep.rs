@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+/// Run the game loops at a fixed rate, independently of the frame rate of the
+/// display: the time elapsed every frame is accumulated, then consumed by as
+/// many game loops as it can hold.
+pub struct FixedTimestep {
+    loop_interval: Duration,
+    max_loops_per_frame: u32,
+    accumulated: Duration,
+}
+
+impl FixedTimestep {
+    pub fn new(loops_per_second: u16, max_loops_per_frame: u8) -> Self {
+        Self {
+            loop_interval: Duration::from_secs(1) / u32::from(loops_per_second.max(1)),
+            max_loops_per_frame: u32::from(max_loops_per_frame.max(1)),
+            accumulated: Duration::ZERO,
+        }
+    }
+
+    /// Accumulate the time elapsed since the last frame, returning the number
+    /// of game loops to run in this frame. If the game has fallen behind by
+    /// more than `max_loops_per_frame` loops (e.g. the window was dragged),
+    /// the extra loops are dropped instead of being caught up.
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.accumulated += elapsed;
+        let interval_nanos = self.loop_interval.as_nanos();
+        let n_loops = self.accumulated.as_nanos() / interval_nanos;
+        self.accumulated =
+            Duration::from_nanos((self.accumulated.as_nanos() % interval_nanos) as u64);
+        if n_loops > u128::from(self.max_loops_per_frame) {
+            log::warn!(
+                "Dropping {} game loops!",
+                n_loops - u128::from(self.max_loops_per_frame)
+            );
+            self.max_loops_per_frame
+        } else {
+            n_loops as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let mut timestep = FixedTimestep::new(50, 3);
+        let millis = Duration::from_millis;
+        // Loops run every 20 millis, whatever the frame rate.
+        assert_eq!(timestep.advance(millis(7)), 0);
+        assert_eq!(timestep.advance(millis(7)), 0);
+        assert_eq!(timestep.advance(millis(7)), 1);
+        assert_eq!(timestep.advance(millis(39)), 2);
+        // Too far behind: catch up 3 loops at most, and keep the remainder.
+        assert_eq!(timestep.advance(millis(1000 + 19)), 3);
+        assert_eq!(timestep.advance(millis(1)), 1);
+    }
+}
@@ -1,39 +1,64 @@
 use macroquad::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use tetris_game::{Button, Color as TetrisColor, GamePad, GameUI, Position};
 
 use crate::conf::{GamePadSettings, UISettings};
 
 pub struct MacroquadGamePad {
-    pressed: HashMap<Button, bool>,
+    held_loops: HashMap<Button, u32>,
+    // Buttons pressed since the last game loop, so that short taps between 2
+    // game loops are not missed.
+    pressed: HashSet<Button>,
     cheat_code: Option<char>,
 }
 
 impl MacroquadGamePad {
-    const KEY_CODE_MAPPINGS: [(KeyCode, Button); 8] = [
+    const KEY_CODE_MAPPINGS: [(KeyCode, Button); 12] = [
         (KeyCode::Left, Button::Left),
         (KeyCode::Right, Button::Right),
         (KeyCode::Up, Button::Up),
         (KeyCode::Down, Button::Down),
         (KeyCode::Space, Button::A),
         (KeyCode::Tab, Button::B),
+        (KeyCode::LeftShift, Button::X),
+        (KeyCode::LeftAlt, Button::Y),
+        (KeyCode::RightShift, Button::L),
         (KeyCode::LeftControl, Button::Select),
         (KeyCode::Enter, Button::Start),
+        (KeyCode::Escape, Button::Pause),
     ];
 
     pub fn new(_settings: &GamePadSettings) -> Self {
         Self {
-            pressed: HashMap::new(),
+            held_loops: HashMap::new(),
+            pressed: HashSet::new(),
             cheat_code: None,
         }
     }
 
+    /// Record the buttons pressed in this frame. To be called every frame,
+    /// even if no game loop runs in it.
+    pub fn poll_input(&mut self) {
+        for (key_code, button) in Self::KEY_CODE_MAPPINGS.iter() {
+            if is_key_pressed(*key_code) {
+                self.pressed.insert(*button);
+            }
+        }
+    }
+
+    /// Update the state of the buttons for a new game loop.
     pub fn refresh_input(&mut self) {
         Self::KEY_CODE_MAPPINGS
             .iter()
             .for_each(|(key_code, button)| {
-                self.pressed.insert(*button, is_key_pressed(*key_code));
+                let is_pressed = self.pressed.remove(button);
+                let held_loops = self.held_loops.entry(*button).or_default();
+                *held_loops = if is_key_down(*key_code) || is_pressed {
+                    *held_loops + 1
+                } else {
+                    0
+                };
             });
 
         match get_char_pressed() {
@@ -44,26 +69,8 @@ impl MacroquadGamePad {
 }
 
 impl GamePad for MacroquadGamePad {
-    fn direction(&self) -> (i16, i16) {
-        let mut offset_x = 0;
-        let mut offset_y = 0;
-        if self.is_pressed(Button::Left) {
-            offset_x -= 1;
-        }
-        if self.is_pressed(Button::Right) {
-            offset_x += 1;
-        }
-        if self.is_pressed(Button::Up) {
-            offset_y -= 1;
-        }
-        if self.is_pressed(Button::Down) {
-            offset_y += 1;
-        }
-        (offset_x, offset_y)
-    }
-
-    fn is_pressed(&self, button: Button) -> bool {
-        *self.pressed.get(&button).unwrap_or(&false)
+    fn held_loops(&self, button: Button) -> u32 {
+        *self.held_loops.get(&button).unwrap_or(&0)
     }
 
     fn cheat_code(&self) -> Option<char> {
@@ -173,6 +180,43 @@ impl<'a> GameUI for MacroquadUI<'a> {
         );
     }
 
+    /// Draw a ghost brick as a translucent brick with an outline.
+    fn draw_ghost_brick(&mut self, pos: Position, color: TetrisColor) {
+        let (screen_x, screen_y) = self.to_screen_xy(pos.xy());
+        let color = to_color(color);
+        draw_rectangle(
+            screen_x,
+            screen_y,
+            self.brick_size(),
+            self.brick_size(),
+            translucent(&color),
+        );
+        draw_rectangle_lines(
+            screen_x,
+            screen_y,
+            self.brick_size(),
+            self.brick_size(),
+            self.brick_shadow(),
+            color,
+        );
+    }
+
+    /// Draw a brick being cleared as a brick fading to white.
+    fn draw_clearing_brick(&mut self, pos: Position, color: TetrisColor, progress: f32) {
+        self.draw_brick(pos, color);
+        let (screen_x, screen_y) = self.to_screen_xy(pos.xy());
+        draw_rectangle(
+            screen_x,
+            screen_y,
+            self.brick_size(),
+            self.brick_size(),
+            Color {
+                a: progress.clamp(0.0, 1.0),
+                ..WHITE
+            },
+        );
+    }
+
     fn draw_text(&mut self, pos: Position, msg: &str) {
         let (screen_x, screen_y) = self.to_screen_xy(pos.xy());
         draw_text(msg, screen_x, screen_y, self.font_size(), WHITE);
@@ -212,4 +256,8 @@ fn lighten(color: &Color) -> Color {
     }
 }
 
+fn translucent(color: &Color) -> Color {
+    Color { a: 0.25, ..*color }
+}
+
 // endregion